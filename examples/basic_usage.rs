@@ -40,7 +40,7 @@ fn main() {
     
     // Example 2: Converting to owned with default symbols
     println!("\n2. Converting to owned (default symbols):");
-    let borrowed = Expression::Symbol("hello");
+    let borrowed = Expression::Symbol("hello".into());
     let owned: OwnedExpression<StringOwnedSymbol> = borrowed.to_owned();
     println!("   Borrowed: {:?}", borrowed);
     println!("   Owned: {}", owned);
@@ -57,7 +57,7 @@ fn main() {
     let complex = OwnedExpression::<SimpleSymbol>::List(vec![
         OwnedExpression::Symbol(SimpleSymbol::from_str("define")),
         OwnedExpression::Symbol(SimpleSymbol::from_str("x")),
-        OwnedExpression::Number(42.0),
+        OwnedExpression::Integer(42),
     ]);
     println!("   Complex: {}", complex);
     
@@ -127,7 +127,7 @@ fn main() {
     
     // Example 4: Converting from borrowed expressions
     println!("\n4. Converting from Borrowed Expressions:");
-    let borrowed = Expression::Symbol("std::vector");
+    let borrowed = Expression::Symbol("std::vector".into());
     let owned_ns: OwnedExpression<NamespacedSymbol> = borrowed.to_owned();
     println!("   Borrowed: {:?}", borrowed);
     println!("   Owned: {}", owned_ns);
@@ -136,9 +136,9 @@ fn main() {
     println!("\n5. Complex Expressions with Custom Symbols:");
     let complex_expr = OwnedExpression::<NamespacedSymbol>::List(vec![
         OwnedExpression::Symbol(NamespacedSymbol::from_str("std::vector")),
-        OwnedExpression::Number(1.0),
-        OwnedExpression::Number(2.0),
-        OwnedExpression::Number(3.0),
+        OwnedExpression::Integer(1),
+        OwnedExpression::Integer(2),
+        OwnedExpression::Integer(3),
     ]);
     println!("   {}", complex_expr);
     
@@ -0,0 +1,437 @@
+//! Arena Allocation Module
+//!
+//! This module parses into [`ArenaExpression`], a tree with the same variant
+//! shape as [`Expression`] whose nodes and child slices live in an
+//! [`Arena`]. A whole parse makes a handful of large bump allocations
+//! instead of one `Vec` per list, and freeing the tree is a single drop of
+//! the arena. It is available with the `arena` feature.
+//!
+//! # Features
+//!
+//! - **Bump allocation**: Child slices are allocated in the arena, built from
+//!   one scratch stack that is reused for every list
+//! - **Zero-copy atoms**: Symbols and strings borrow from the source; only
+//!   strings with escapes are copied, into the arena
+//! - **`Copy` nodes**: Every node is a small `Copy` value holding references
+//! - **Same syntax**: Shares the reader's parser, so it reads exactly what
+//!   [`read_with`](crate::read_with) does, with the same errors
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read_all_in, read_in, Arena, ArenaExpression};
+//!
+//! let arena = Arena::new();
+//! let expr = read_in(&arena, "(define (square x) (* x x))").unwrap();
+//! match expr {
+//!     ArenaExpression::List([ArenaExpression::Symbol("define"), signature, _body]) => {
+//!         assert_eq!(signature.to_string(), "(square x)");
+//!     }
+//!     other => panic!("unexpected {}", other),
+//! }
+//!
+//! let forms = read_all_in(&arena, "(a) (b) c").unwrap();
+//! assert_eq!(forms.len(), 3);
+//! ```
+
+use std::borrow::Cow;
+#[cfg(feature = "bignum")]
+use std::cell::{Cell, OnceCell};
+use std::fmt;
+use std::vec::Drain;
+
+use bumpalo::Bump;
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+#[cfg(feature = "bignum")]
+use num_rational::BigRational;
+
+use crate::error::ParseError;
+use crate::lexer::{write_char, write_quoted};
+use crate::number::write_float;
+use crate::options::ReaderOptions;
+use crate::reader::{write_delimited, write_map, write_quote, write_symbol, Builder, Expression, Parser, QuoteKind};
+
+/// The memory an [`ArenaExpression`] tree lives in.
+///
+/// Dropping the arena frees every tree parsed into it at once.
+#[derive(Default)]
+pub struct Arena {
+    bump: Bump,
+    /// Big numbers own heap memory, so they are kept where their
+    /// destructors run when the arena is dropped
+    #[cfg(feature = "bignum")]
+    big_ints: Store<BigInt>,
+    #[cfg(feature = "bignum")]
+    ratios: Store<BigRational>,
+}
+
+impl Arena {
+    /// Create an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an arena with room for `bytes` bytes of nodes before it needs
+    /// to grow.
+    ///
+    /// A good estimate for a parse is a few times the source length.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Arena {
+            bump: Bump::with_capacity(bytes),
+            #[cfg(feature = "bignum")]
+            big_ints: Store::default(),
+            #[cfg(feature = "bignum")]
+            ratios: Store::default(),
+        }
+    }
+
+    /// The number of bytes allocated for nodes, strings and slices so far.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena").field("allocated_bytes", &self.allocated_bytes()).finish_non_exhaustive()
+    }
+}
+
+/// An append-only store for values that own heap memory.
+///
+/// Slot `i` lives in chunk `log2(i + 1)`, and chunk `c` holds `2^c` slots.
+/// Chunks are allocated once and never resized, so stored values never move
+/// and can be borrowed for as long as the store lives.
+#[cfg(feature = "bignum")]
+struct Store<T> {
+    chunks: [OnceCell<Box<[OnceCell<T>]>>; usize::BITS as usize],
+    len: Cell<usize>,
+}
+
+#[cfg(feature = "bignum")]
+impl<T> Store<T> {
+    /// Move `value` into the store.
+    fn alloc(&self, value: T) -> &T {
+        let index = self.len.get();
+        self.len.set(index + 1);
+        let chunk = (index + 1).ilog2();
+        let slots = self.chunks[chunk as usize].get_or_init(|| (0..1usize << chunk).map(|_| OnceCell::new()).collect());
+        slots[index + 1 - (1 << chunk)].get_or_init(|| value)
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl<T> Default for Store<T> {
+    fn default() -> Self {
+        Store { chunks: [const { OnceCell::new() }; usize::BITS as usize], len: Cell::new(0) }
+    }
+}
+
+/// An S-expression whose nodes live in an [`Arena`].
+///
+/// The variants mirror [`Expression`], with slices and references in
+/// place of `Vec`, `Box` and `Cow`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaExpression<'a> {
+    /// An exact integer literal such as `42` or `-7`
+    Integer(i64),
+    /// A floating point literal such as `1.0` or `6.02e23`
+    Float(f64),
+    /// An integer literal outside the range of `i64`
+    #[cfg(feature = "bignum")]
+    BigInt(&'a BigInt),
+    /// An exact, non-integral rational literal such as `1/3`
+    #[cfg(feature = "bignum")]
+    Ratio(&'a BigRational),
+    /// A boolean literal
+    Bool(bool),
+    /// A string literal
+    Str(&'a str),
+    /// A character literal such as `#\a`
+    Char(char),
+    /// A symbol/identifier
+    Symbol(&'a str),
+    /// A keyword, stored without its colon
+    Keyword(&'a str),
+    /// A list of expressions
+    List(&'a [ArenaExpression<'a>]),
+    /// An improper list: the elements before the dot and the tail after it.
+    /// With no elements before the dot it prints as its tail.
+    DottedList(&'a [ArenaExpression<'a>], &'a ArenaExpression<'a>),
+    /// A vector literal such as `[a b]`
+    Vector(&'a [ArenaExpression<'a>]),
+    /// A map literal such as `{k v}`, as key-value pairs in source order
+    Map(&'a [(ArenaExpression<'a>, ArenaExpression<'a>)]),
+    /// A set literal such as `#{a b}`
+    Set(&'a [ArenaExpression<'a>]),
+    /// A bytevector such as `#u8(1 2 255)`
+    Bytes(&'a [u8]),
+    /// A value with a display hint such as `#[text/plain hello]`
+    Hinted(&'a ArenaExpression<'a>, &'a ArenaExpression<'a>),
+    /// A quoted expression such as `'x` (only with
+    /// [`QuoteStyle::Variant`](crate::QuoteStyle::Variant))
+    Quoted(QuoteKind, &'a ArenaExpression<'a>),
+    /// A null value
+    Null,
+}
+
+impl<'a> ArenaExpression<'a> {
+    /// Convert to an [`Expression`] that borrows the same strings.
+    ///
+    /// This allocates `Vec`s for the lists, so it is meant for passing a
+    /// subtree to code that takes an `Expression`, not for whole trees.
+    pub fn to_expression(&self) -> Expression<'a> {
+        let all = |items: &'a [ArenaExpression<'a>]| items.iter().map(ArenaExpression::to_expression).collect();
+        match *self {
+            ArenaExpression::Integer(n) => Expression::Integer(n),
+            ArenaExpression::Float(n) => Expression::Float(n),
+            #[cfg(feature = "bignum")]
+            ArenaExpression::BigInt(n) => Expression::BigInt(n.clone()),
+            #[cfg(feature = "bignum")]
+            ArenaExpression::Ratio(r) => Expression::Ratio(r.clone()),
+            ArenaExpression::Bool(b) => Expression::Bool(b),
+            ArenaExpression::Str(s) => Expression::Str(s.into()),
+            ArenaExpression::Char(c) => Expression::Char(c),
+            ArenaExpression::Symbol(s) => Expression::Symbol(s.into()),
+            ArenaExpression::Keyword(k) => Expression::Keyword(k.into()),
+            ArenaExpression::List(items) => Expression::List(all(items)),
+            ArenaExpression::DottedList(items, tail) => Expression::DottedList(all(items), Box::new(tail.to_expression())),
+            ArenaExpression::Vector(items) => Expression::Vector(all(items)),
+            ArenaExpression::Map(entries) => {
+                Expression::Map(entries.iter().map(|(key, value)| (key.to_expression(), value.to_expression())).collect())
+            }
+            ArenaExpression::Set(items) => Expression::Set(all(items)),
+            ArenaExpression::Bytes(bytes) => Expression::Bytes(bytes.into()),
+            ArenaExpression::Hinted(hint, value) => {
+                Expression::Hinted(Box::new(hint.to_expression()), Box::new(value.to_expression()))
+            }
+            ArenaExpression::Quoted(kind, inner) => Expression::Quoted(kind, Box::new(inner.to_expression())),
+            ArenaExpression::Null => Expression::Null,
+        }
+    }
+}
+
+impl<'a> fmt::Display for ArenaExpression<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaExpression::Integer(n) => write!(f, "{}", n),
+            ArenaExpression::Float(n) => write_float(f, *n),
+            #[cfg(feature = "bignum")]
+            ArenaExpression::BigInt(n) => write!(f, "{}", n),
+            #[cfg(feature = "bignum")]
+            ArenaExpression::Ratio(r) => write!(f, "{}", r),
+            ArenaExpression::Bool(b) => write!(f, "{}", b),
+            ArenaExpression::Str(s) => write_quoted(f, s, '"'),
+            ArenaExpression::Char(c) => write_char(f, *c),
+            ArenaExpression::Symbol(s) => write_symbol(f, s),
+            ArenaExpression::Keyword(k) => {
+                write!(f, ":")?;
+                write_symbol(f, k)
+            }
+            ArenaExpression::List(list) => write_delimited(f, "(", list, ")"),
+            // With no elements before the dot, the list is just its tail
+            ArenaExpression::DottedList(list, tail) if list.is_empty() => write!(f, "{}", tail),
+            ArenaExpression::DottedList(list, tail) => {
+                write_delimited(f, "(", list, "")?;
+                write!(f, " . {})", tail)
+            }
+            ArenaExpression::Vector(items) => write_delimited(f, "[", items, "]"),
+            ArenaExpression::Map(entries) => write_map(f, entries),
+            ArenaExpression::Set(items) => write_delimited(f, "#{", items, "}"),
+            ArenaExpression::Bytes(bytes) => write_delimited(f, "#u8(", bytes, ")"),
+            ArenaExpression::Hinted(hint, value) => write!(f, "#[{} {}]", hint, value),
+            ArenaExpression::Quoted(kind, expr) => write_quote(f, *kind, expr),
+            ArenaExpression::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// Parse the first expression of `src` into `arena`.
+///
+/// # Arguments
+///
+/// * `arena` - The arena the tree is allocated in
+/// * `src` - The source string to parse
+///
+/// # Returns
+///
+/// The root of the tree, borrowing from both `arena` and `src`
+///
+/// # Errors
+///
+/// Returns the same [`ParseError`]s as [`read`](crate::read)
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read_in, Arena};
+///
+/// let arena = Arena::new();
+/// let expr = read_in(&arena, "(a (1 2) \"x\\ny\" . z)").unwrap();
+/// assert_eq!(expr.to_string(), "(a (1 2) \"x\\ny\" . z)");
+/// ```
+pub fn read_in<'a>(arena: &'a Arena, src: &'a str) -> Result<ArenaExpression<'a>, ParseError> {
+    read_in_with(arena, src, &ReaderOptions::default())
+}
+
+/// Parse the first expression of `src` into `arena` with the given options.
+///
+/// # Errors
+///
+/// Returns the same [`ParseError`]s as [`read_with`](crate::read_with)
+pub fn read_in_with<'a>(arena: &'a Arena, src: &'a str, options: &ReaderOptions) -> Result<ArenaExpression<'a>, ParseError> {
+    Parser::with_builder(src, options, ArenaBuilder { arena }).parse()
+}
+
+/// Parse every top-level form of `src` into `arena`.
+///
+/// # Returns
+///
+/// The forms in source order, as a slice in the arena
+///
+/// # Errors
+///
+/// Returns the first [`ParseError`] in the source
+pub fn read_all_in<'a>(arena: &'a Arena, src: &'a str) -> Result<&'a [ArenaExpression<'a>], ParseError> {
+    let mut parser = Parser::with_builder(src, &ReaderOptions::default(), ArenaBuilder { arena });
+    let mut forms = Vec::new();
+    while !parser.is_at_end()? {
+        forms.push(parser.parse()?);
+    }
+    Ok(arena.bump.alloc_slice_copy(&forms))
+}
+
+/// Builds [`ArenaExpression`] trees in an [`Arena`].
+struct ArenaBuilder<'a> {
+    arena: &'a Arena,
+}
+
+impl<'a> ArenaBuilder<'a> {
+    /// Move a string into the arena unless it already borrows from the
+    /// source.
+    fn text(&self, s: Cow<'a, str>) -> &'a str {
+        match s {
+            Cow::Borrowed(s) => s,
+            Cow::Owned(s) => self.arena.bump.alloc_str(&s),
+        }
+    }
+
+    /// Move collection elements off the parser's stack and into the arena.
+    fn slice(&self, items: Drain<'_, ArenaExpression<'a>>) -> &'a [ArenaExpression<'a>] {
+        self.arena.bump.alloc_slice_fill_iter(items)
+    }
+}
+
+impl<'a> Builder<'a> for ArenaBuilder<'a> {
+    type Node = ArenaExpression<'a>;
+
+    fn leaf(&mut self, expr: Expression<'a>) -> ArenaExpression<'a> {
+        match expr {
+            Expression::Integer(n) => ArenaExpression::Integer(n),
+            Expression::Float(n) => ArenaExpression::Float(n),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => ArenaExpression::BigInt(self.arena.big_ints.alloc(n)),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(r) => ArenaExpression::Ratio(self.arena.ratios.alloc(r)),
+            Expression::Bool(b) => ArenaExpression::Bool(b),
+            Expression::Str(s) => ArenaExpression::Str(self.text(s)),
+            Expression::Char(c) => ArenaExpression::Char(c),
+            Expression::Symbol(s) => ArenaExpression::Symbol(self.text(s)),
+            Expression::Keyword(k) => ArenaExpression::Keyword(self.text(k)),
+            Expression::Null => ArenaExpression::Null,
+            _ => unreachable!("leaves are atoms, strings or characters"),
+        }
+    }
+
+    fn is_byte(&self, node: &ArenaExpression<'a>) -> bool {
+        matches!(node, ArenaExpression::Integer(0..=255))
+    }
+
+    fn list(&mut self, items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        ArenaExpression::List(self.slice(items))
+    }
+
+    fn dotted_list(&mut self, items: Drain<'_, ArenaExpression<'a>>, tail: ArenaExpression<'a>) -> ArenaExpression<'a> {
+        ArenaExpression::DottedList(self.slice(items), self.arena.bump.alloc(tail))
+    }
+
+    fn vector(&mut self, items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        ArenaExpression::Vector(self.slice(items))
+    }
+
+    fn map(&mut self, items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        let pairs = items.as_slice().chunks_exact(2).map(|pair| (pair[0], pair[1]));
+        ArenaExpression::Map(self.arena.bump.alloc_slice_fill_iter(pairs))
+    }
+
+    fn set(&mut self, items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        ArenaExpression::Set(self.slice(items))
+    }
+
+    fn bytes(&mut self, items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        ArenaExpression::Bytes(self.arena.bump.alloc_slice_fill_iter(items.map(|item| match item {
+            ArenaExpression::Integer(n) => n as u8,
+            _ => unreachable!("bytevector elements are checked as they are read"),
+        })))
+    }
+
+    fn hinted(&mut self, mut items: Drain<'_, ArenaExpression<'a>>) -> ArenaExpression<'a> {
+        match (items.next(), items.next()) {
+            (Some(hint), Some(value)) => ArenaExpression::Hinted(self.arena.bump.alloc(hint), self.arena.bump.alloc(value)),
+            _ => unreachable!("display hints are checked as they are read"),
+        }
+    }
+
+    fn quoted(&mut self, kind: QuoteKind, expr: ArenaExpression<'a>) -> ArenaExpression<'a> {
+        ArenaExpression::Quoted(kind, self.arena.bump.alloc(expr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Dialect;
+    use crate::reader::{read_all, read_with};
+
+    #[test]
+    fn read_in_test() {
+        let arena = Arena::new();
+        let src = "(define (f x . rest) [1 -2.5 #\\a] {:k #{s}} \"esc\\t\" |odd sym| 'q `(a ,b) #u8(0 255) #;skipped null)";
+        let options = ReaderOptions::new();
+        let expr = read_in_with(&arena, src, &options).unwrap();
+        assert_eq!(expr.to_expression(), read_with(src, &options).unwrap());
+        assert_eq!(expr.to_string(), read_with(src, &options).unwrap().to_string());
+
+        let options = ReaderOptions::new().dialect(Dialect::Clojure);
+        let src = "(defn f [x] '(~x))";
+        assert_eq!(read_in_with(&arena, src, &options).unwrap().to_expression(), read_with(src, &options).unwrap());
+
+        let src = "(a 10) b\n(c (d))";
+        let forms = read_all_in(&arena, src).unwrap();
+        assert_eq!(forms.iter().map(ArenaExpression::to_expression).collect::<Vec<_>>(), read_all(src).unwrap());
+        assert!(read_all_in(&arena, " ").unwrap().is_empty());
+    }
+
+    #[cfg(feature = "bignum")]
+    #[test]
+    fn bignum_test() {
+        let arena = Arena::new();
+        let src = "(123456789012345678901234567890 2/6)";
+        assert_eq!(read_in(&arena, src).unwrap().to_string(), "(123456789012345678901234567890 1/3)");
+
+        let src = (0..100).map(|i| format!("{}{:02}", "9".repeat(20), i)).collect::<Vec<_>>().join(" ");
+        let forms = read_all_in(&arena, &src).unwrap();
+        assert_eq!(forms.iter().map(ArenaExpression::to_string).collect::<Vec<_>>().join(" "), src);
+    }
+
+    #[test]
+    fn error_test() {
+        let arena = Arena::new();
+        for src in ["", ")", "(a", "[a", "(a]", "(. a)", "(a . b c)", "{a}", "#u8(256)", "(\"a\\q\")", "'"] {
+            let options = ReaderOptions::new();
+            assert_eq!(read_in_with(&arena, src, &options).unwrap_err(), read_with(src, &options).unwrap_err(), "{}", src);
+        }
+        assert!(matches!(read_all_in(&arena, "(a) )"), Err(ParseError::UnexpectedClosingParen { .. })));
+    }
+}
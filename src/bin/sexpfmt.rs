@@ -0,0 +1,111 @@
+//! Reformat S-expression source files.
+//!
+//! ```text
+//! sexpfmt [--check] [--width N] [--dialect NAME] [FILE...]
+//! ```
+//!
+//! Files are rewritten in place. Without files, source is read from stdin
+//! and the formatted result written to stdout. With `--check`, nothing is
+//! written and the exit status is 1 if any file is not formatted.
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use sexpression::{format, Dialect, FormatOptions, PrettyOptions, ReaderOptions};
+
+const USAGE: &str = "usage: sexpfmt [--check] [--width N] [--dialect sexpression|scheme|common-lisp|clojure|rivest] [FILE...]";
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut pretty = PrettyOptions::new();
+    let mut reader = ReaderOptions::new();
+    let mut files = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--width" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(width) => pretty = pretty.width(width),
+                None => return usage_error("--width expects a number"),
+            },
+            "--dialect" => match args.next().as_deref().and_then(parse_dialect) {
+                Some(dialect) => reader = reader.dialect(dialect),
+                None => return usage_error("unknown dialect"),
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => return usage_error(&format!("unknown option {}", arg)),
+            _ => files.push(arg),
+        }
+    }
+    let options = FormatOptions::new().reader(reader).pretty(pretty);
+
+    if files.is_empty() {
+        let mut src = String::new();
+        if let Err(err) = io::stdin().read_to_string(&mut src) {
+            eprintln!("sexpfmt: <stdin>: {}", err);
+            return ExitCode::from(2);
+        }
+        return match format(&src, &options) {
+            Ok(formatted) if check => if formatted == src { ExitCode::SUCCESS } else { ExitCode::from(1) },
+            Ok(formatted) => match io::stdout().write_all(formatted.as_bytes()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(_) => ExitCode::from(2),
+            },
+            Err(err) => {
+                eprint!("<stdin>: {}", err.render(&src));
+                ExitCode::from(2)
+            }
+        };
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for file in &files {
+        match format_file(file, &options, check) {
+            Ok(true) if check => {
+                println!("{}", file);
+                status = ExitCode::from(1);
+            }
+            Ok(_) => {}
+            Err(message) => {
+                eprint!("{}", message);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    status
+}
+
+/// Format one file in place, or only compare it when `check` is set.
+///
+/// Returns whether the file was not formatted, or an error message.
+fn format_file(path: &str, options: &FormatOptions, check: bool) -> Result<bool, String> {
+    let src = std::fs::read_to_string(path).map_err(|err| format!("sexpfmt: {}: {}\n", path, err))?;
+    let formatted = format(&src, options).map_err(|err| format!("{}: {}", path, err.render(&src)))?;
+    if formatted == src {
+        return Ok(false);
+    }
+    if !check {
+        std::fs::write(path, formatted).map_err(|err| format!("sexpfmt: {}: {}\n", path, err))?;
+    }
+    Ok(true)
+}
+
+fn parse_dialect(name: &str) -> Option<Dialect> {
+    Some(match name {
+        "sexpression" => Dialect::Sexpression,
+        "scheme" => Dialect::Scheme,
+        "common-lisp" => Dialect::CommonLisp,
+        "clojure" => Dialect::Clojure,
+        "rivest" => Dialect::Rivest,
+        _ => return None,
+    })
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("sexpfmt: {}\n{}", message, USAGE);
+    ExitCode::from(2)
+}
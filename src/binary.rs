@@ -0,0 +1,641 @@
+//! Binary Encoding Module
+//!
+//! This module provides a compact binary encoding of expression trees, for
+//! caching parsed sources between runs instead of reading the text again.
+//! Decoding is zero-copy: [`Expression::from_bytes`] borrows every string,
+//! symbol and bytevector from the encoded buffer.
+//!
+//! # Format
+//!
+//! An encoding starts with the magic bytes `SEXB` and a version byte, then
+//! a string table holding each distinct symbol and keyword name once, then
+//! the root expression. Every node is a one-byte tag followed by its
+//! payload; integers are zigzag varints, and lengths, counts and string
+//! table indices are unsigned LEB128 varints.
+//!
+//! # Features
+//!
+//! - **Compact**: Small integers take one or two bytes, and repeated symbols
+//!   are stored once and referenced by index
+//! - **Versioned**: Data from an unknown format version is rejected with
+//!   [`DecodeError::UnsupportedVersion`] instead of being misread
+//! - **Zero-copy decoding**: Decoded expressions borrow from the buffer
+//! - **Lossless**: Every expression variant round-trips exactly, including
+//!   floats, bytevectors and big numbers
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read, Expression, OwnedExpression};
+//!
+//! let expr = read("(define (square x) (* x x))").unwrap();
+//! let bytes = expr.to_bytes();
+//! assert_eq!(Expression::from_bytes(&bytes).unwrap(), expr);
+//!
+//! let owned: OwnedExpression = OwnedExpression::from_bytes(&bytes).unwrap();
+//! assert_eq!(owned.to_bytes(), bytes);
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+#[cfg(feature = "bignum")]
+use num_rational::BigRational;
+
+use crate::error::DecodeError;
+use crate::reader::{Expression, OwnedExpression, OwnedSymbol, QuoteKind};
+
+/// The magic bytes every encoding starts with.
+const MAGIC: &[u8; 4] = b"SEXB";
+
+/// The current format version.
+const VERSION: u8 = 1;
+
+/// The deepest nesting the decoder accepts, counting the root as 1, so
+/// corrupt or hostile data cannot overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+const NULL: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const INTEGER: u8 = 3;
+const FLOAT: u8 = 4;
+const BIG_INT: u8 = 5;
+const RATIO: u8 = 6;
+const STR: u8 = 7;
+const CHAR: u8 = 8;
+const SYMBOL: u8 = 9;
+const KEYWORD: u8 = 10;
+const LIST: u8 = 11;
+const DOTTED_LIST: u8 = 12;
+const VECTOR: u8 = 13;
+const MAP: u8 = 14;
+const SET: u8 = 15;
+const BYTES: u8 = 16;
+const QUOTED: u8 = 17;
+const HINTED: u8 = 18;
+
+impl<'a> Expression<'a> {
+    /// Encode this expression in the compact binary format.
+    ///
+    /// # Returns
+    ///
+    /// The encoded bytes, which [`Expression::from_bytes`] and
+    /// [`OwnedExpression::from_bytes`] read back
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.expression(self);
+        encoder.finish()
+    }
+
+    /// Decode an expression from the compact binary format without copying.
+    ///
+    /// Strings, symbols, keywords and bytevectors borrow from `bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Data produced by a `to_bytes` method
+    ///
+    /// # Returns
+    ///
+    /// The decoded expression
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] if the data is truncated, corrupt, nested
+    /// more than 256 levels deep, has trailing bytes or comes from an
+    /// unsupported format version
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use sexpression::{read, Expression};
+    ///
+    /// let bytes = read("(greet \"world\")").unwrap().to_bytes();
+    /// let expr = Expression::from_bytes(&bytes).unwrap();
+    /// assert!(matches!(&expr, Expression::List(items) if matches!(items[1], Expression::Str(Cow::Borrowed("world")))));
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Expression<'a>, DecodeError> {
+        let mut decoder = Decoder { bytes, pos: 0, names: Vec::new(), depth: 0 };
+        if !bytes.starts_with(MAGIC) {
+            return Err(DecodeError::BadMagic);
+        }
+        decoder.pos = MAGIC.len();
+        match decoder.byte()? {
+            VERSION => {}
+            version => return Err(DecodeError::UnsupportedVersion(version)),
+        }
+        let count = decoder.length()?;
+        decoder.names = Vec::with_capacity(count.min(decoder.remaining()));
+        for _ in 0..count {
+            let name = decoder.str()?;
+            decoder.names.push(name);
+        }
+        let expr = decoder.expression()?;
+        if decoder.pos < bytes.len() {
+            return Err(DecodeError::TrailingData { offset: decoder.pos });
+        }
+        Ok(expr)
+    }
+}
+
+impl<S: OwnedSymbol> OwnedExpression<S> {
+    /// Encode this expression in the compact binary format.
+    ///
+    /// Symbol and keyword names come from [`OwnedSymbol::symbol_name`]. See
+    /// [`Expression::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.owned(self);
+        encoder.finish()
+    }
+
+    /// Decode an owned expression from the compact binary format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] if the data is truncated, corrupt, nested
+    /// more than 256 levels deep, has trailing bytes or comes from an
+    /// unsupported format version
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Expression::from_bytes(bytes)?.to_owned())
+    }
+}
+
+/// Writes the body of an encoding while collecting its string table.
+#[derive(Default)]
+struct Encoder<'e> {
+    body: Vec<u8>,
+    /// Symbol and keyword names in order of first use
+    names: Vec<Cow<'e, str>>,
+    /// The string table index of each name
+    indices: HashMap<Cow<'e, str>, u64>,
+}
+
+impl<'e> Encoder<'e> {
+    /// The whole encoding: the header, the string table and the body.
+    fn finish(self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAGIC.len() + 1 + self.body.len() + 8 * self.names.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(&mut out, self.names.len() as u64);
+        for name in &self.names {
+            write_varint(&mut out, name.len() as u64);
+            out.extend_from_slice(name.as_bytes());
+        }
+        out.extend_from_slice(&self.body);
+        out
+    }
+
+    fn expression(&mut self, expr: &'e Expression<'_>) {
+        match expr {
+            Expression::Null => self.body.push(NULL),
+            Expression::Bool(false) => self.body.push(FALSE),
+            Expression::Bool(true) => self.body.push(TRUE),
+            Expression::Integer(n) => {
+                self.body.push(INTEGER);
+                // Zigzag encoding keeps small negative numbers short
+                write_varint(&mut self.body, ((*n << 1) ^ (*n >> 63)) as u64);
+            }
+            Expression::Float(n) => {
+                self.body.push(FLOAT);
+                self.body.extend_from_slice(&n.to_bits().to_le_bytes());
+            }
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => {
+                self.body.push(BIG_INT);
+                self.big_int(n);
+            }
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(r) => {
+                self.body.push(RATIO);
+                self.big_int(r.numer());
+                self.big_int(r.denom());
+            }
+            Expression::Str(s) => {
+                self.body.push(STR);
+                self.octets(s.as_bytes());
+            }
+            Expression::Char(c) => {
+                self.body.push(CHAR);
+                write_varint(&mut self.body, u64::from(*c));
+            }
+            Expression::Symbol(name) => {
+                self.body.push(SYMBOL);
+                self.name(Cow::Borrowed(name));
+            }
+            Expression::Keyword(name) => {
+                self.body.push(KEYWORD);
+                self.name(Cow::Borrowed(name));
+            }
+            Expression::List(items) => self.sequence(LIST, items),
+            Expression::DottedList(items, tail) => {
+                self.sequence(DOTTED_LIST, items);
+                self.expression(tail);
+            }
+            Expression::Vector(items) => self.sequence(VECTOR, items),
+            Expression::Map(entries) => {
+                self.body.push(MAP);
+                write_varint(&mut self.body, entries.len() as u64);
+                for (key, value) in entries {
+                    self.expression(key);
+                    self.expression(value);
+                }
+            }
+            Expression::Set(items) => self.sequence(SET, items),
+            Expression::Bytes(bytes) => {
+                self.body.push(BYTES);
+                self.octets(bytes);
+            }
+            Expression::Hinted(hint, value) => {
+                self.body.push(HINTED);
+                self.expression(hint);
+                self.expression(value);
+            }
+            Expression::Quoted(kind, inner) => {
+                self.body.push(QUOTED);
+                self.body.push(*kind as u8);
+                self.expression(inner);
+            }
+        }
+    }
+
+    fn sequence(&mut self, tag: u8, items: &'e [Expression<'_>]) {
+        self.body.push(tag);
+        write_varint(&mut self.body, items.len() as u64);
+        for item in items {
+            self.expression(item);
+        }
+    }
+
+    /// Write an owned expression exactly as [`expression`](Self::expression)
+    /// writes the borrowed one.
+    fn owned<S: OwnedSymbol>(&mut self, expr: &'e OwnedExpression<S>) {
+        match expr {
+            OwnedExpression::Null => self.body.push(NULL),
+            OwnedExpression::Bool(false) => self.body.push(FALSE),
+            OwnedExpression::Bool(true) => self.body.push(TRUE),
+            OwnedExpression::Integer(n) => {
+                self.body.push(INTEGER);
+                write_varint(&mut self.body, ((*n << 1) ^ (*n >> 63)) as u64);
+            }
+            OwnedExpression::Float(n) => {
+                self.body.push(FLOAT);
+                self.body.extend_from_slice(&n.to_bits().to_le_bytes());
+            }
+            #[cfg(feature = "bignum")]
+            OwnedExpression::BigInt(n) => {
+                self.body.push(BIG_INT);
+                self.big_int(n);
+            }
+            #[cfg(feature = "bignum")]
+            OwnedExpression::Ratio(r) => {
+                self.body.push(RATIO);
+                self.big_int(r.numer());
+                self.big_int(r.denom());
+            }
+            OwnedExpression::Str(s) => {
+                self.body.push(STR);
+                self.octets(s.as_bytes());
+            }
+            OwnedExpression::Char(c) => {
+                self.body.push(CHAR);
+                write_varint(&mut self.body, u64::from(*c));
+            }
+            OwnedExpression::Symbol(symbol) => {
+                self.body.push(SYMBOL);
+                self.name(symbol.symbol_name());
+            }
+            OwnedExpression::Keyword(symbol) => {
+                self.body.push(KEYWORD);
+                self.name(symbol.symbol_name());
+            }
+            OwnedExpression::List(items) => self.owned_sequence(LIST, items),
+            OwnedExpression::DottedList(items, tail) => {
+                self.owned_sequence(DOTTED_LIST, items);
+                self.owned(tail);
+            }
+            OwnedExpression::Vector(items) => self.owned_sequence(VECTOR, items),
+            OwnedExpression::Map(entries) => {
+                self.body.push(MAP);
+                write_varint(&mut self.body, entries.len() as u64);
+                for (key, value) in entries {
+                    self.owned(key);
+                    self.owned(value);
+                }
+            }
+            OwnedExpression::Set(items) => self.owned_sequence(SET, items),
+            OwnedExpression::Bytes(bytes) => {
+                self.body.push(BYTES);
+                self.octets(bytes);
+            }
+            OwnedExpression::Hinted(hint, value) => {
+                self.body.push(HINTED);
+                self.owned(hint);
+                self.owned(value);
+            }
+            OwnedExpression::Quoted(kind, inner) => {
+                self.body.push(QUOTED);
+                self.body.push(*kind as u8);
+                self.owned(inner);
+            }
+        }
+    }
+
+    fn owned_sequence<S: OwnedSymbol>(&mut self, tag: u8, items: &'e [OwnedExpression<S>]) {
+        self.body.push(tag);
+        write_varint(&mut self.body, items.len() as u64);
+        for item in items {
+            self.owned(item);
+        }
+    }
+
+    fn octets(&mut self, bytes: &[u8]) {
+        write_varint(&mut self.body, bytes.len() as u64);
+        self.body.extend_from_slice(bytes);
+    }
+
+    /// Write the string table index of `name`, adding it if it is new.
+    fn name(&mut self, name: Cow<'e, str>) {
+        let index = match self.indices.get(&name) {
+            Some(&index) => index,
+            None => {
+                let index = self.names.len() as u64;
+                self.names.push(name.clone());
+                self.indices.insert(name, index);
+                index
+            }
+        };
+        write_varint(&mut self.body, index);
+    }
+
+    #[cfg(feature = "bignum")]
+    fn big_int(&mut self, n: &BigInt) {
+        self.octets(&n.to_signed_bytes_le());
+    }
+}
+
+/// Append `n` as an unsigned LEB128 varint.
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Reads an encoding, borrowing from the buffer.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// The string table
+    names: Vec<&'a str>,
+    /// The number of expressions being decoded, from the root down
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn expression(&mut self) -> Result<Expression<'a>, DecodeError> {
+        let offset = self.pos;
+        if self.depth == MAX_DEPTH {
+            return Err(DecodeError::Invalid { offset, what: "nesting depth" });
+        }
+        self.depth += 1;
+        let expr = match self.byte()? {
+            NULL => Expression::Null,
+            FALSE => Expression::Bool(false),
+            TRUE => Expression::Bool(true),
+            INTEGER => {
+                let n = self.varint()?;
+                Expression::Integer((n >> 1) as i64 ^ -((n & 1) as i64))
+            }
+            FLOAT => {
+                let bits = self.take(8)?.try_into().expect("took exactly 8 bytes");
+                Expression::Float(f64::from_bits(u64::from_le_bytes(bits)))
+            }
+            #[cfg(feature = "bignum")]
+            BIG_INT => Expression::BigInt(self.big_int()?),
+            #[cfg(feature = "bignum")]
+            RATIO => {
+                let (numer, denom) = (self.big_int()?, self.big_int()?);
+                if denom == BigInt::from(0) {
+                    return Err(DecodeError::Invalid { offset, what: "ratio" });
+                }
+                Expression::Ratio(BigRational::new(numer, denom))
+            }
+            #[cfg(not(feature = "bignum"))]
+            BIG_INT | RATIO => return Err(DecodeError::Invalid { offset, what: "number (requires the `bignum` feature)" }),
+            STR => Expression::Str(Cow::Borrowed(self.str()?)),
+            CHAR => {
+                let code = self.varint()?;
+                let c = u32::try_from(code).ok().and_then(char::from_u32);
+                Expression::Char(c.ok_or(DecodeError::Invalid { offset, what: "character" })?)
+            }
+            SYMBOL => Expression::Symbol(Cow::Borrowed(self.name()?)),
+            KEYWORD => Expression::Keyword(Cow::Borrowed(self.name()?)),
+            LIST => Expression::List(self.sequence()?),
+            DOTTED_LIST => {
+                let items = self.sequence()?;
+                let tail = self.expression()?;
+                Expression::DottedList(items, Box::new(tail))
+            }
+            VECTOR => Expression::Vector(self.sequence()?),
+            MAP => {
+                let count = self.length()?;
+                let mut entries = Vec::with_capacity(count.min(self.remaining()));
+                for _ in 0..count {
+                    let key = self.expression()?;
+                    entries.push((key, self.expression()?));
+                }
+                Expression::Map(entries)
+            }
+            SET => Expression::Set(self.sequence()?),
+            BYTES => {
+                let len = self.length()?;
+                Expression::Bytes(Cow::Borrowed(self.take(len)?))
+            }
+            QUOTED => {
+                let kind = match self.byte()? {
+                    0 => QuoteKind::Quote,
+                    1 => QuoteKind::Quasiquote,
+                    2 => QuoteKind::Unquote,
+                    3 => QuoteKind::UnquoteSplicing,
+                    _ => return Err(DecodeError::Invalid { offset: offset + 1, what: "quote kind" }),
+                };
+                Expression::Quoted(kind, Box::new(self.expression()?))
+            }
+            HINTED => {
+                let hint = self.expression()?;
+                Expression::Hinted(Box::new(hint), Box::new(self.expression()?))
+            }
+            _ => return Err(DecodeError::Invalid { offset, what: "tag" }),
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Expression<'a>>, DecodeError> {
+        let count = self.length()?;
+        // Every element takes at least one byte, so corrupt counts cannot
+        // reserve more than the input could hold
+        let mut items = Vec::with_capacity(count.min(self.remaining()));
+        for _ in 0..count {
+            items.push(self.expression()?);
+        }
+        Ok(items)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.remaining() < len {
+            return Err(DecodeError::UnexpectedEnd { offset: self.bytes.len() });
+        }
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let offset = self.pos;
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(DecodeError::Invalid { offset, what: "varint" })
+    }
+
+    fn length(&mut self) -> Result<usize, DecodeError> {
+        let offset = self.pos;
+        usize::try_from(self.varint()?).map_err(|_| DecodeError::Invalid { offset, what: "length" })
+    }
+
+    fn str(&mut self) -> Result<&'a str, DecodeError> {
+        let len = self.length()?;
+        let offset = self.pos;
+        std::str::from_utf8(self.take(len)?).map_err(|_| DecodeError::Invalid { offset, what: "string" })
+    }
+
+    /// Look up a string table entry by its index.
+    fn name(&mut self) -> Result<&'a str, DecodeError> {
+        let offset = self.pos;
+        let index = self.varint()?;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.names.get(index).copied())
+            .ok_or(DecodeError::Invalid { offset, what: "string table index" })
+    }
+
+    #[cfg(feature = "bignum")]
+    fn big_int(&mut self) -> Result<BigInt, DecodeError> {
+        let len = self.length()?;
+        Ok(BigInt::from_signed_bytes_le(self.take(len)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::InternedSymbol;
+    use crate::reader::{read, StringOwnedSymbol};
+
+    #[test]
+    fn round_trip_test() {
+        let src = "(define (f x . rest) [1 -1 0 9223372036854775807 -9223372036854775808] {:k #{x}} \
+                   \"str\\n\" #\\λ 1.5 -0.0 true false null 'q `(a ,b ,@c) #u8(0 255) #[text/plain hi] |odd name|)";
+        let expr = read(src).unwrap();
+        let bytes = expr.to_bytes();
+        assert_eq!(Expression::from_bytes(&bytes).unwrap(), expr);
+        assert_eq!(OwnedExpression::<StringOwnedSymbol>::from_bytes(&bytes).unwrap(), expr.to_owned());
+
+        let bytes = Expression::Float(f64::NAN).to_bytes();
+        let nan = Expression::from_bytes(&bytes).unwrap();
+        assert!(matches!(nan, Expression::Float(n) if n.is_nan()));
+    }
+
+    #[test]
+    fn owned_test() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Plain(String);
+
+        impl OwnedSymbol for Plain {
+            fn from_str(s: &str) -> Self {
+                Plain(s.to_string())
+            }
+
+            fn display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        let expr = read("(f |a b| :k 1.5 \"s\" x x)").unwrap();
+        let bytes = expr.to_bytes();
+        let interned: OwnedExpression<InternedSymbol> = expr.to_owned();
+        assert_eq!(interned.to_bytes(), bytes);
+        assert_eq!(OwnedExpression::<InternedSymbol>::from_bytes(&bytes).unwrap(), interned);
+        let plain: OwnedExpression<Plain> = expr.to_owned();
+        assert_eq!(plain.to_bytes(), bytes);
+        assert_eq!(OwnedExpression::<Plain>::from_bytes(&bytes).unwrap(), plain);
+    }
+
+    #[test]
+    fn string_table_test() {
+        let expr = read("(lambda (lambda) (lambda lambda :lambda))").unwrap();
+        let bytes = expr.to_bytes();
+        assert_eq!(bytes.windows(6).filter(|w| w == b"lambda").count(), 1);
+        assert_eq!(&bytes[..5], b"SEXB\x01");
+        assert_eq!(read("42").unwrap().to_bytes(), b"SEXB\x01\x00\x03\x54");
+
+        match Expression::from_bytes(&bytes).unwrap() {
+            Expression::List(items) => assert!(matches!(items[0], Expression::Symbol(Cow::Borrowed("lambda")))),
+            other => panic!("expected a list, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "bignum")]
+    #[test]
+    fn bignum_test() {
+        let expr = read("(123456789012345678901234567890 -98765432109876543210 1/3 -22/7)").unwrap();
+        assert_eq!(Expression::from_bytes(&expr.to_bytes()).unwrap(), expr);
+    }
+
+    #[test]
+    fn error_test() {
+        let bytes = read("(a \"b\")").unwrap().to_bytes();
+        assert_eq!(Expression::from_bytes(b"SEXP\x01"), Err(DecodeError::BadMagic));
+        assert_eq!(Expression::from_bytes(b"SEXB\x02\x00\x00"), Err(DecodeError::UnsupportedVersion(2)));
+        assert_eq!(Expression::from_bytes(&bytes[..bytes.len() - 1]), Err(DecodeError::UnexpectedEnd { offset: bytes.len() - 1 }));
+        assert_eq!(Expression::from_bytes(&[&bytes[..], b"\x00"].concat()), Err(DecodeError::TrailingData { offset: bytes.len() }));
+        assert_eq!(Expression::from_bytes(b"SEXB\x01\x00\x63"), Err(DecodeError::Invalid { offset: 6, what: "tag" }));
+        assert_eq!(Expression::from_bytes(b"SEXB\x01\x00\x09\x00"), Err(DecodeError::Invalid { offset: 7, what: "string table index" }));
+        assert_eq!(Expression::from_bytes(b"SEXB\x01\x00\x07\x01\xff"), Err(DecodeError::Invalid { offset: 8, what: "string" }));
+        assert_eq!(Expression::from_bytes(b"SEXB\x01\x00\x0b\xff\xff\xff\xff\x0f"), Err(DecodeError::UnexpectedEnd { offset: 12 }));
+    }
+
+    #[test]
+    fn depth_test() {
+        let nested = |lists: usize| [&b"SEXB\x01\x00"[..], &[LIST, 1].repeat(lists), &[NULL]].concat();
+        assert!(Expression::from_bytes(&nested(MAX_DEPTH - 1)).is_ok());
+        assert_eq!(
+            Expression::from_bytes(&nested(MAX_DEPTH)),
+            Err(DecodeError::Invalid { offset: 6 + 2 * MAX_DEPTH, what: "nesting depth" })
+        );
+        assert_eq!(
+            Expression::from_bytes(&nested(1_000_000)),
+            Err(DecodeError::Invalid { offset: 6 + 2 * MAX_DEPTH, what: "nesting depth" })
+        );
+    }
+}
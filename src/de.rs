@@ -0,0 +1,460 @@
+//! Serde Deserializer Module
+//!
+//! This module reads S-expression text into any [`Deserialize`] type. The
+//! text is parsed into a borrowed [`Expression`] first, and [`Expression`]
+//! itself implements serde's [`Deserializer`](de::Deserializer), so strings
+//! and symbols without escapes can be borrowed straight from the source. It
+//! is available with the `serde` feature.
+//!
+//! # Features
+//!
+//! - **Structs**: Read from keyword-argument lists `(:name "web" :port 80)`,
+//!   alists `((name . "web") (port . 80))` or maps `{:name "web"}`
+//! - **Enums**: Read from a symbol for unit variants, or a tagged list such as
+//!   `(Circle 1.5)` or `(Rect :w 2 :h 3)`
+//! - **Options**: `null` is `None`, anything else is `Some`
+//! - **Maps**: Read from `{k v}` map literals, alists or keyword lists
+//! - **Round trip**: Everything written by [`to_string`](crate::to_string)
+//!   reads back
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug, PartialEq)]
+//! struct Server<'a> {
+//!     name: &'a str,
+//!     port: u16,
+//!     backup: Option<String>,
+//! }
+//!
+//! let server: Server = sexpression::from_str("(:name \"web\" :port 80)").unwrap();
+//! assert_eq!(server, Server { name: "web", port: 80, backup: None });
+//!
+//! let server: Server = sexpression::from_str("((name . \"db\") (port . 5432) (backup . \"db2\"))").unwrap();
+//! assert_eq!(server.backup.as_deref(), Some("db2"));
+//! ```
+
+use std::borrow::Cow;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, IntoDeserializer, Unexpected, Visitor};
+
+use crate::error::SerdeError;
+use crate::reader::{read_strict, Expression};
+
+/// Read a value from S-expression text.
+///
+/// The text must hold exactly one expression, optionally surrounded by
+/// comments. It is read with the default
+/// [`ReaderOptions`](crate::ReaderOptions), so everything
+/// [`to_string`](crate::to_string) writes reads back.
+///
+/// # Arguments
+///
+/// * `src` - The source text
+///
+/// # Returns
+///
+/// The deserialized value, which may borrow from `src`
+///
+/// # Errors
+///
+/// Returns [`SerdeError::Parse`] if the text is not a single valid
+/// S-expression, and [`SerdeError::Message`] if it does not match the shape
+/// of `T`
+///
+/// # Examples
+///
+/// ```rust
+/// let pairs: Vec<(String, i32)> = sexpression::from_str("((a . 1) (b . 2))").unwrap();
+/// assert_eq!(pairs, vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+/// ```
+pub fn from_str<'a, T: Deserialize<'a>>(src: &'a str) -> Result<T, SerdeError> {
+    T::deserialize(read_strict(src)?)
+}
+
+/// Read a value from an already parsed expression.
+///
+/// # Errors
+///
+/// Returns [`SerdeError::Message`] if the expression does not match the
+/// shape of `T`
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{from_expression, read};
+///
+/// let flags: Vec<bool> = from_expression(read("(true false)").unwrap()).unwrap();
+/// assert_eq!(flags, vec![true, false]);
+/// ```
+pub fn from_expression<'a, T: Deserialize<'a>>(expr: Expression<'a>) -> Result<T, SerdeError> {
+    T::deserialize(expr)
+}
+
+impl<'de> Expression<'de> {
+    /// The serde description of this expression, for error messages.
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Expression::Integer(n) => Unexpected::Signed(*n),
+            Expression::Float(n) => Unexpected::Float(*n),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(_) => Unexpected::Other("big integer"),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(_) => Unexpected::Other("ratio"),
+            Expression::Bool(b) => Unexpected::Bool(*b),
+            Expression::Str(s) => Unexpected::Str(s),
+            Expression::Char(c) => Unexpected::Char(*c),
+            Expression::Symbol(_) => Unexpected::Other("symbol"),
+            Expression::Keyword(_) => Unexpected::Other("keyword"),
+            Expression::List(_) | Expression::Vector(_) | Expression::Set(_) => Unexpected::Seq,
+            Expression::DottedList(..) => Unexpected::Other("dotted list"),
+            Expression::Map(_) => Unexpected::Map,
+            Expression::Bytes(bytes) => Unexpected::Bytes(bytes),
+            Expression::Hinted(..) => Unexpected::Other("display hint"),
+            Expression::Quoted(..) => Unexpected::Other("quoted expression"),
+            Expression::Null => Unexpected::Unit,
+        }
+    }
+
+    /// The key-value pairs of a map literal, keyword list or alist.
+    ///
+    /// Returns the expression unchanged if it is none of these.
+    fn into_entries(self) -> Result<Vec<(Expression<'de>, Expression<'de>)>, Expression<'de>> {
+        match self {
+            Expression::Map(entries) => Ok(entries),
+            Expression::List(items) if is_keyword_list(&items) => {
+                let mut items = items.into_iter();
+                let mut entries = Vec::with_capacity(items.len() / 2);
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    entries.push((key, value));
+                }
+                Ok(entries)
+            }
+            Expression::List(items) if items.iter().all(is_pair) => Ok(items
+                .into_iter()
+                .map(|item| match item {
+                    Expression::DottedList(mut head, tail) => (head.remove(0), *tail),
+                    _ => unreachable!("checked by is_pair"),
+                })
+                .collect()),
+            other => Err(other),
+        }
+    }
+}
+
+/// Returns `true` for `(:k1 v1 :k2 v2 ...)`, including the empty list.
+fn is_keyword_list(items: &[Expression<'_>]) -> bool {
+    items.len().is_multiple_of(2) && items.iter().step_by(2).all(|key| matches!(key, Expression::Keyword(_)))
+}
+
+/// Returns `true` for an alist entry `(key . value)`.
+fn is_pair(item: &Expression<'_>) -> bool {
+    matches!(item, Expression::DottedList(head, _) if head.len() == 1)
+}
+
+/// Visit the elements of a sequence, failing if any are left over.
+fn visit_seq<'de, V: Visitor<'de>>(items: Vec<Expression<'de>>, visitor: V) -> Result<V::Value, SerdeError> {
+    let mut seq = SeqDeserializer::new(items.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+/// Visit key-value pairs as a map, failing if any are left over.
+fn visit_map<'de, V: Visitor<'de>>(
+    entries: Vec<(Expression<'de>, Expression<'de>)>,
+    visitor: V,
+) -> Result<V::Value, SerdeError> {
+    let mut map = MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+/// Visit a string, borrowing it from the source when possible.
+fn visit_cow_str<'de, V: Visitor<'de>>(s: Cow<'de, str>, visitor: V) -> Result<V::Value, SerdeError> {
+    match s {
+        Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+        Cow::Owned(s) => visitor.visit_string(s),
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Expression<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Expression<'de> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Expression::Integer(n) => visitor.visit_i64(n),
+            Expression::Float(n) => visitor.visit_f64(n),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => match (u64::try_from(&n), i128::try_from(&n), u128::try_from(&n)) {
+                (Ok(n), _, _) => visitor.visit_u64(n),
+                (_, Ok(n), _) => visitor.visit_i128(n),
+                (_, _, Ok(n)) => visitor.visit_u128(n),
+                _ => Err(de::Error::invalid_value(Unexpected::Other("big integer"), &visitor)),
+            },
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(_) => Err(de::Error::invalid_type(Unexpected::Other("ratio"), &visitor)),
+            Expression::Bool(b) => visitor.visit_bool(b),
+            Expression::Str(s) | Expression::Symbol(s) | Expression::Keyword(s) => visit_cow_str(s, visitor),
+            Expression::Char(c) => visitor.visit_char(c),
+            Expression::List(items) | Expression::Vector(items) | Expression::Set(items) => visit_seq(items, visitor),
+            Expression::DottedList(mut items, tail) => {
+                items.push(*tail);
+                visit_seq(items, visitor)
+            }
+            Expression::Map(entries) => visit_map(entries, visitor),
+            Expression::Bytes(Cow::Borrowed(bytes)) => visitor.visit_borrowed_bytes(bytes),
+            Expression::Bytes(Cow::Owned(bytes)) => visitor.visit_byte_buf(bytes),
+            Expression::Hinted(hint, value) => visit_seq(vec![*hint, *value], visitor),
+            Expression::Quoted(kind, expr) => {
+                visit_seq(vec![Expression::Symbol(Cow::Borrowed(kind.symbol())), *expr], visitor)
+            }
+            Expression::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Expression::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Expression::Bytes(bytes) => visit_seq(bytes.iter().map(|&b| Expression::Integer(b.into())).collect(), visitor),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Expression::Null => visitor.visit_unit(),
+            Expression::List(items) if items.is_empty() => visitor.visit_unit(),
+            other => Err(de::Error::invalid_type(other.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.into_entries() {
+            Ok(entries) => visit_map(entries, visitor),
+            Err(other) => Err(de::Error::invalid_type(other.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self.into_entries() {
+            Ok(entries) => visit_map(entries, visitor),
+            // Positional fields, as in `(1 2)` for `Point { x, y }`
+            Err(other) => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self {
+            Expression::Symbol(name) | Expression::Str(name) | Expression::Keyword(name) => {
+                visitor.visit_enum(Enum { variant: name, args: None })
+            }
+            Expression::List(mut items) if matches!(items.first(), Some(Expression::Symbol(_))) => {
+                let Expression::Symbol(name) = items.remove(0) else { unreachable!("checked above") };
+                visitor.visit_enum(Enum { variant: name, args: Some(items) })
+            }
+            other => Err(de::Error::invalid_type(other.unexpected(), &"a symbol or a tagged list")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf identifier ignored_any
+    }
+}
+
+/// An enum variant: its name and, for tagged lists, the arguments after it.
+struct Enum<'de> {
+    variant: Cow<'de, str>,
+    args: Option<Vec<Expression<'de>>>,
+}
+
+impl<'de> de::EnumAccess<'de> for Enum<'de> {
+    type Error = SerdeError;
+    type Variant = Self;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(mut self, seed: T) -> Result<(T::Value, Self), SerdeError> {
+        let name = std::mem::take(&mut self.variant);
+        Ok((seed.deserialize(Expression::Symbol(name))?, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Enum<'de> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        match self.args {
+            None => Ok(()),
+            Some(args) if args.is_empty() => Ok(()),
+            Some(_) => Err(de::Error::invalid_type(Unexpected::TupleVariant, &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, SerdeError> {
+        match self.args {
+            Some(mut args) if args.len() == 1 => seed.deserialize(args.remove(0)),
+            Some(args) => Err(de::Error::invalid_length(args.len(), &"one argument")),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.args {
+            Some(args) => visit_seq(args, visitor),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self.args {
+            Some(args) => de::Deserializer::deserialize_struct(Expression::List(args), "", fields, visitor),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"struct variant")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ser::to_string;
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashSet};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect(u32, u32),
+        Labeled { name: String, sides: Option<u8> },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        shapes: Vec<Shape>,
+        limits: BTreeMap<String, i64>,
+        tags: HashSet<char>,
+        ratio: (f32, bool),
+        #[serde(default)]
+        retries: Option<u8>,
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let config = Config {
+            name: "a \"quoted\" name".into(),
+            shapes: vec![Shape::Empty, Shape::Circle(0.5), Shape::Rect(1, 2), Shape::Labeled {
+                name: "tri".into(),
+                sides: None,
+            }],
+            limits: BTreeMap::from([("cpu".into(), 4), ("mem".into(), -1)]),
+            tags: HashSet::from(['x']),
+            ratio: (1.5, true),
+            retries: Some(3),
+        };
+        let text = to_string(&config).unwrap();
+        assert_eq!(from_str::<Config>(&text).unwrap(), config);
+        assert_eq!(from_str::<Vec<u8>>("#u8(1 2 255)").unwrap(), vec![1, 2, 255]);
+        assert_eq!(from_str::<(u8, u8)>("#u8(7 8)").unwrap(), (7, 8));
+    }
+
+    #[test]
+    fn struct_forms_test() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Point<'a> {
+            x: i32,
+            y: i32,
+            label: Option<&'a str>,
+        }
+        let expected = Point { x: 1, y: 2, label: None };
+        assert_eq!(from_str::<Point>("(:x 1 :y 2)").unwrap(), expected);
+        assert_eq!(from_str::<Point>("((x . 1) (y . 2))").unwrap(), expected);
+        assert_eq!(from_str::<Point>("{:x 1 y 2 \"label\" null}").unwrap(), expected);
+        assert_eq!(from_str::<Point>("(1 2 null)").unwrap(), expected);
+
+        let point: Point = from_str("(:x 1 :y 2 :label \"origin\") ; trailing comment").unwrap();
+        assert_eq!(point.label, Some("origin"));
+        let map: BTreeMap<&str, u8> = from_str("(:a 1 :b 2)").unwrap();
+        assert_eq!(map, BTreeMap::from([("a", 1), ("b", 2)]));
+        assert_eq!(from_str::<()>("()").unwrap(), ());
+    }
+
+    #[test]
+    fn error_test() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Port {
+            port: u16,
+        }
+        assert!(matches!(from_str::<Port>("(:port"), Err(SerdeError::Parse(_))));
+        assert!(matches!(from_str::<Port>("(:port 1) extra"), Err(SerdeError::Parse(_))));
+        let message = |src| match from_str::<Port>(src) {
+            Err(SerdeError::Message(message)) => message,
+            other => panic!("expected a message, got {:?}", other),
+        };
+        assert_eq!(message("(:port \"80\")"), "invalid type: string \"80\", expected u16");
+        assert_eq!(message("(:port 70000)"), "invalid value: integer `70000`, expected u16");
+        assert_eq!(message("()"), "missing field `port`");
+        assert_eq!(message("42"), "invalid type: integer `42`, expected struct Port");
+        assert!(from_str::<Shape>("(Circle 1 2)").is_err());
+        assert!(from_str::<Shape>("Circle").is_err());
+        assert!(from_str::<Shape>("(Empty 1)").is_err());
+    }
+}
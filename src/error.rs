@@ -0,0 +1,475 @@
+//! Parse Error Module
+//!
+//! This module defines [`ParseError`], the error type returned by every
+//! parsing entry point. Each variant records the [`Span`] of the offending
+//! source text, and [`ParseError::render`] turns an error into a
+//! caret-annotated snippet of the original source for display to users.
+//! [`EncodeError`] reports values that cannot be written in one of the
+//! Rivest encodings or JSON, and [`DecodeError`] data that is not a valid binary
+//! encoding. With the `serde` feature, `SerdeError` is the error type
+//! for converting between Rust values and S-expressions.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read, ParseError};
+//!
+//! let src = "(define (f x)\n  (+ x 1)";
+//! let err = read(src).unwrap_err();
+//! assert!(matches!(err, ParseError::MissingClosingParen { .. }));
+//! assert_eq!(err.span().line, 2);
+//! println!("{}", err.render(src));
+//! ```
+
+use std::fmt::Write;
+
+use crate::span::Span;
+
+/// Parse errors that can occur during S-expression parsing.
+///
+/// This enum provides detailed error information for debugging and
+/// error handling in production environments. Every variant carries the
+/// [`Span`] at which the problem was detected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ParseError {
+    /// Unexpected end of input while parsing
+    #[error("Unexpected EOF at line {}, column {}, expected an expression", .span.line, .span.column)]
+    UnexpectedEOF {
+        /// The (empty) span at the end of the input
+        span: Span,
+    },
+    /// Missing closing parenthesis in a list
+    #[error(
+        "Missing closing parenthesis for '(' opened at line {}, column {}",
+        .open.line, .open.column
+    )]
+    MissingClosingParen {
+        /// The (empty) span at the end of the input
+        span: Span,
+        /// The span of the unmatched opening parenthesis
+        open: Span,
+    },
+    /// Missing closing bracket or brace in a vector, map or set
+    #[error(
+        "Missing closing '{expected}' for opening delimiter at line {}, column {}",
+        .open.line, .open.column
+    )]
+    MissingClosingBracket {
+        /// The (empty) span at the end of the input
+        span: Span,
+        /// The span of the unmatched opening delimiter
+        open: Span,
+        /// The closing delimiter that was expected
+        expected: char,
+    },
+    /// Unexpected closing parenthesis, bracket or brace (no matching opening delimiter)
+    #[error("Unexpected '{found}' at line {}, column {}, expected an expression", .span.line, .span.column)]
+    UnexpectedClosingParen {
+        /// The span of the closing delimiter
+        span: Span,
+        /// The closing delimiter that was found
+        found: char,
+    },
+    /// A collection was closed with the wrong delimiter, as in `(a]`
+    #[error("Mismatched '{found}' at line {}, column {}, expected '{expected}'", .span.line, .span.column)]
+    MismatchedBracket {
+        /// The span of the wrong closing delimiter
+        span: Span,
+        /// The span of the opening delimiter
+        open: Span,
+        /// The closing delimiter that was expected
+        expected: char,
+        /// The closing delimiter that was found
+        found: char,
+    },
+    /// A `.` appears where a dotted list does not allow it, as in `(. a)` or `(a .)`
+    #[error("Misplaced '{token}' at line {}, column {}", .span.line, .span.column)]
+    MisplacedDot {
+        /// The span of the dot
+        span: Span,
+        /// The dot as written in the source
+        token: String,
+    },
+    /// A dotted list has more than one expression after the dot, as in `(a . b c)`
+    #[error(
+        "Expected ')' after the tail of a dotted list at line {}, column {}, found '{token}'",
+        .span.line, .span.column
+    )]
+    ExtraAfterDottedTail {
+        /// The span of the first token after the tail
+        span: Span,
+        /// The first token after the tail as written in the source
+        token: String,
+    },
+    /// A bytevector holds something other than an integer from 0 to 255
+    #[error(
+        "Bytevector element '{token}' at line {}, column {} is not an integer from 0 to 255",
+        .span.line, .span.column
+    )]
+    InvalidByte {
+        /// The span of the first token of the element
+        span: Span,
+        /// The first token of the element as written in the source, which
+        /// is the whole element unless it is a collection
+        token: String,
+    },
+    /// A map literal has a key without a value
+    #[error("Map literal at line {}, column {} has an odd number of elements", .span.line, .span.column)]
+    UnpairedMapKey {
+        /// The span of the map literal
+        span: Span,
+    },
+    /// A display hint does not hold exactly a hint and a value
+    #[error("Display hint at line {}, column {} must have exactly two elements", .span.line, .span.column)]
+    InvalidDisplayHint {
+        /// The span of the display hint
+        span: Span,
+    },
+    /// Input remained after a complete expression was read in strict mode
+    #[error("Unexpected trailing '{token}' at line {}, column {}, expected end of input", .span.line, .span.column)]
+    TrailingInput {
+        /// The span of the first token after the expression
+        span: Span,
+        /// The first token after the expression as written in the source
+        token: String,
+    },
+    /// A string literal was not closed before the end of input
+    #[error("Unterminated string literal starting at line {}, column {}", .span.line, .span.column)]
+    UnterminatedString {
+        /// The span from the opening quote to the end of the input
+        span: Span,
+        /// The unterminated literal as written in the source
+        token: String,
+    },
+    /// An integer literal does not fit in the integer type
+    #[error("Integer literal {literal} out of range at line {}, column {}", .span.line, .span.column)]
+    IntegerOverflow {
+        /// The span of the literal
+        span: Span,
+        /// The literal as written in the source
+        literal: String,
+    },
+    /// A literal has number syntax but does not denote a valid number
+    #[error("Invalid number literal {literal} at line {}, column {}", .span.line, .span.column)]
+    InvalidNumber {
+        /// The span of the literal
+        span: Span,
+        /// The literal as written in the source
+        literal: String,
+    },
+    /// A block comment was not closed before the end of input
+    #[error("Unterminated block comment starting at line {}, column {}", .span.line, .span.column)]
+    UnterminatedComment {
+        /// The span from the opening `#|` to the end of the input
+        span: Span,
+        /// The unterminated comment as written in the source
+        token: String,
+    },
+    /// A `|...|` section of a symbol was not closed before the end of input
+    #[error("Unterminated '|' in symbol starting at line {}, column {}", .span.line, .span.column)]
+    UnterminatedSymbol {
+        /// The span from the opening `|` to the end of the input
+        span: Span,
+        /// The unterminated part of the symbol as written in the source
+        token: String,
+    },
+    /// A string literal contained an unknown or malformed escape sequence
+    #[error("Invalid escape sequence {sequence} at line {}, column {}", .span.line, .span.column)]
+    InvalidEscape {
+        /// The span of the escape sequence
+        span: Span,
+        /// The offending escape sequence as written in the source
+        sequence: String,
+    },
+    /// A verbatim string's length runs past the end of the input
+    #[error("Verbatim string at line {}, column {} is shorter than its length prefix", .span.line, .span.column)]
+    InvalidVerbatim {
+        /// The span from the length prefix to the end of the input
+        span: Span,
+    },
+    /// Rivest input is malformed, e.g. a bad length prefix or base64 data
+    #[error("Invalid {what} at line {}, column {}", .span.line, .span.column)]
+    InvalidEncoding {
+        /// The span of the malformed input
+        span: Span,
+        /// What was being read, e.g. `"length prefix"`
+        what: &'static str,
+    },
+    /// A `#\` character literal names no character
+    #[error("Invalid character literal {literal} at line {}, column {}", .span.line, .span.column)]
+    InvalidCharacter {
+        /// The span of the literal
+        span: Span,
+        /// The literal as written in the source
+        literal: String,
+    },
+}
+
+impl ParseError {
+    /// The location in the source at which the error was detected.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedEOF { span }
+            | ParseError::MissingClosingParen { span, .. }
+            | ParseError::MissingClosingBracket { span, .. }
+            | ParseError::UnexpectedClosingParen { span, .. }
+            | ParseError::MismatchedBracket { span, .. }
+            | ParseError::UnpairedMapKey { span }
+            | ParseError::InvalidDisplayHint { span }
+            | ParseError::InvalidByte { span, .. }
+            | ParseError::MisplacedDot { span, .. }
+            | ParseError::ExtraAfterDottedTail { span, .. }
+            | ParseError::TrailingInput { span, .. }
+            | ParseError::UnterminatedString { span, .. }
+            | ParseError::UnterminatedComment { span, .. }
+            | ParseError::UnterminatedSymbol { span, .. }
+            | ParseError::InvalidEscape { span, .. }
+            | ParseError::InvalidCharacter { span, .. }
+            | ParseError::InvalidVerbatim { span }
+            | ParseError::InvalidEncoding { span, .. }
+            | ParseError::IntegerOverflow { span, .. }
+            | ParseError::InvalidNumber { span, .. } => *span,
+        }
+    }
+
+    /// Render the error as a caret-annotated snippet of the source.
+    ///
+    /// The output names the error, points at its line and column, and
+    /// underlines the offending text. Errors that relate to a second
+    /// location, such as the opening parenthesis of an unclosed list, also
+    /// show that location. Spans that run past the end of `src` are cut
+    /// short rather than causing a panic.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source string that produced the error
+    ///
+    /// # Returns
+    ///
+    /// A multi-line, human-readable description of the error
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::read;
+    ///
+    /// let src = "(print \"hi\\q\")";
+    /// let err = read(src).unwrap_err();
+    /// assert_eq!(err.render(src), "\
+    /// error: Invalid escape sequence \\q at line 1, column 11
+    ///  --> 1:11
+    ///   |
+    /// 1 | (print \"hi\\q\")
+    ///   |           ^^
+    /// ");
+    /// ```
+    pub fn render(&self, src: &str) -> String {
+        let mut out = format!("error: {}\n", self);
+        write_snippet(&mut out, src, self.span(), None);
+        match self {
+            ParseError::MissingClosingParen { open, .. } | ParseError::MissingClosingBracket { open, .. } => {
+                let label = format!("unclosed '{}' opened here", clamped_text(src, *open));
+                write_snippet(&mut out, src, *open, Some(&label));
+            }
+            ParseError::MismatchedBracket { open, .. } => {
+                let label = format!("'{}' opened here", clamped_text(src, *open));
+                write_snippet(&mut out, src, *open, Some(&label));
+            }
+            _ => {}
+        }
+        out
+    }
+}
+
+/// Append a ` --> line:column` header and the underlined source line.
+fn write_snippet(out: &mut String, src: &str, span: Span, label: Option<&str>) {
+    let (start, end) = clamp(src, span);
+    let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+    let line = src[line_start..line_end].trim_end_matches('\r');
+
+    // Underline at least one column, and never past the end of the line
+    let width = src[start..end.min(line_end)].chars().count().max(1);
+    let indent = src[line_start..start].chars().count();
+    let gutter = " ".repeat(span.line.to_string().len());
+
+    let _ = writeln!(out, "{}--> {}:{}", gutter, span.line, span.column);
+    let _ = writeln!(out, "{} |", gutter);
+    let _ = writeln!(out, "{} | {}", span.line, line);
+    let _ = write!(out, "{} | {}{}", gutter, " ".repeat(indent), "^".repeat(width));
+    match label {
+        Some(label) => { let _ = writeln!(out, " {}", label); }
+        None => out.push('\n'),
+    }
+}
+
+/// The source text of `span`, or as much of it as lies within `src`.
+fn clamped_text(src: &str, span: Span) -> &str {
+    let (start, end) = clamp(src, span);
+    &src[start..end]
+}
+
+/// The byte range of `span` limited to `src` and rounded down to character
+/// boundaries, for rendering an error against a different source.
+fn clamp(src: &str, span: Span) -> (usize, usize) {
+    let floor = |offset: usize| {
+        let mut offset = offset.min(src.len());
+        while !src.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    };
+    let start = floor(span.start);
+    (start, floor(span.end).max(start))
+}
+
+/// A value that has no representation in a Rivest encoding or in JSON.
+///
+/// Rivest S-expressions only hold octet strings, lists and display hints, so
+/// floats, keywords, maps and the like cannot be written. Returned by
+/// [`Expression::to_canonical`](crate::Expression::to_canonical) and the
+/// other Rivest writers, and by the JSON conversions of the `json` feature.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} cannot be written in {encoding} form")]
+pub struct EncodeError {
+    /// The kind of value, e.g. `"float"`
+    pub kind: &'static str,
+    /// The encoding being written: `"canonical"`, `"advanced"`, `"transport"`
+    /// or `"JSON"`
+    pub encoding: &'static str,
+}
+
+/// Errors from decoding the compact binary format.
+///
+/// Returned by [`Expression::from_bytes`](crate::Expression::from_bytes)
+/// and [`OwnedExpression::from_bytes`](crate::OwnedExpression::from_bytes).
+/// Offsets are byte positions in the encoded data.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// The data does not start with the format's magic bytes
+    #[error("Data is not a binary S-expression encoding")]
+    BadMagic,
+    /// The data was written by a newer or unknown format version
+    #[error("Unsupported binary format version {0}")]
+    UnsupportedVersion(u8),
+    /// The data ends in the middle of an expression
+    #[error("Unexpected end of binary data at offset {offset}")]
+    UnexpectedEnd {
+        /// The length of the data
+        offset: usize,
+    },
+    /// A tag, length or value is malformed
+    #[error("Invalid {what} at offset {offset}")]
+    Invalid {
+        /// The offset of the malformed value
+        offset: usize,
+        /// What was being decoded, e.g. `"tag"`
+        what: &'static str,
+    },
+    /// Data remains after the root expression
+    #[error("Trailing binary data at offset {offset}")]
+    TrailingData {
+        /// The offset of the first extra byte
+        offset: usize,
+    },
+}
+
+/// Errors from converting between Rust values and S-expressions.
+///
+/// Returned by the `serde` support in [`ser`](crate::ser) and
+/// [`de`](crate::de).
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SerdeError {
+    /// The source text is not a valid S-expression
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// A value could not be converted, e.g. a field has the wrong type
+    #[error("{0}")]
+    Message(String),
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for SerdeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for SerdeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::read;
+
+    #[test]
+    fn render_test() {
+        let src = "(define (f x)\n  (g x)";
+        let err = read(src).unwrap_err();
+        assert_eq!(err.render(src), "\
+error: Missing closing parenthesis for '(' opened at line 1, column 1
+ --> 2:8
+  |
+2 |   (g x)
+  |        ^
+ --> 1:1
+  |
+1 | (define (f x)
+  | ^ unclosed '(' opened here
+");
+
+        let src = "{:a [1 2)}";
+        let err = read(src).unwrap_err();
+        assert_eq!(err.render(src), "\
+error: Mismatched ')' at line 1, column 9, expected ']'
+ --> 1:9
+  |
+1 | {:a [1 2)}
+  |         ^
+ --> 1:5
+  |
+1 | {:a [1 2)}
+  |     ^ '[' opened here
+");
+
+        let src = "(a\n \"unterminated";
+        let err = read(src).unwrap_err();
+        assert_eq!(err.render(src), "\
+error: Unterminated string literal starting at line 2, column 2
+ --> 2:2
+  |
+2 |  \"unterminated
+  |  ^^^^^^^^^^^^^
+");
+
+        let src = "(aaaa \"\u{e9}";
+        let err = read(src).unwrap_err();
+        assert_eq!(err.render("(a"), "\
+error: Unterminated string literal starting at line 1, column 7
+ --> 1:7
+  |
+1 | (a
+  |   ^
+");
+        assert_eq!(read("]").unwrap_err().to_string(), "Unexpected ']' at line 1, column 1, expected an expression");
+        assert_eq!(
+            crate::read_strict("a b").unwrap_err().to_string(),
+            "Unexpected trailing 'b' at line 1, column 3, expected end of input"
+        );
+        assert_eq!(
+            read("#u8(1 x)").unwrap_err().to_string(),
+            "Bytevector element 'x' at line 1, column 7 is not an integer from 0 to 255"
+        );
+        let err = ParseError::MissingClosingParen {
+            span: Span { start: 9, end: 9, line: 1, column: 10 },
+            open: Span { start: 7, end: 8, line: 1, column: 8 },
+        };
+        assert!(err.render("(\u{e9}").contains("unclosed '' opened here"));
+    }
+}
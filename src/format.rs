@@ -0,0 +1,295 @@
+//! Source Formatter Module
+//!
+//! This module reformats whole source files. Unlike
+//! [`Expression::pretty`](crate::Expression::pretty), which prints a parsed
+//! value, the formatter works on the token stream, so everything a reader
+//! would throw away survives: comments, blank lines between forms, `'x`
+//! shorthands and the exact spelling of every atom (`#x1F` stays `#x1F`).
+//! Only the whitespace between tokens changes, and it is laid out by the
+//! same rules as the pretty printer.
+//!
+//! # Features
+//!
+//! - **Comment preserving**: Line, block and datum comments are kept, and a
+//!   comment at the end of a line stays at the end of that line
+//! - **Blank lines**: A run of blank lines between two elements is kept as a
+//!   single blank line
+//! - **Idempotent**: Formatting already formatted source changes nothing
+//! - **Safe**: Source that does not read cleanly is reported as a
+//!   [`ParseError`] instead of being rewritten
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{format, FormatOptions};
+//!
+//! let src = "; math\n(define (square x)   (* x x))\n\n\n(square 4) ; => 16\n";
+//! let formatted = format(src, &FormatOptions::new()).unwrap();
+//! assert_eq!(formatted, "; math\n(define (square x) (* x x))\n\n(square 4) ; => 16\n");
+//! ```
+
+use std::borrow::Cow;
+
+use crate::error::ParseError;
+use crate::lexer::TokenKind;
+use crate::options::ReaderOptions;
+use crate::pretty::{Node, PrettyOptions, Printer};
+use crate::reader::Reader;
+
+/// Configuration for the formatter.
+///
+/// The reader options decide which syntax is recognized, e.g. a
+/// [`Dialect`](crate::Dialect), and the pretty printer options decide the
+/// layout.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub(crate) reader: ReaderOptions,
+    pub(crate) pretty: PrettyOptions,
+}
+
+impl FormatOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the syntax the source is read with.
+    pub fn reader(mut self, options: ReaderOptions) -> Self {
+        self.reader = options;
+        self
+    }
+
+    /// Set the width and indentation rules for the output.
+    pub fn pretty(mut self, options: PrettyOptions) -> Self {
+        self.pretty = options;
+        self
+    }
+}
+
+/// Reformat a source file.
+///
+/// # Arguments
+///
+/// * `src` - The source text of a whole file
+/// * `options` - The syntax and layout to use
+///
+/// # Returns
+///
+/// The formatted source, ending in a newline unless it is empty
+///
+/// # Errors
+///
+/// Returns the first [`ParseError`] of the source; nothing is formatted
+/// unless every form reads cleanly
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{format, FormatOptions, PrettyOptions};
+///
+/// let options = FormatOptions::new().pretty(PrettyOptions::new().width(20));
+/// let src = "(let ((x 1)) ; one\n (print x) (print 'x))";
+/// assert_eq!(format(src, &options).unwrap(), "\
+/// (let ((x 1)) ; one
+///   (print x)
+///   (print 'x))
+/// ");
+/// ```
+pub fn format(src: &str, options: &FormatOptions) -> Result<String, ParseError> {
+    Reader::with_options(src, &options.reader).try_for_each(|form| form.map(drop))?;
+
+    let mut stack = vec![Frame::new("")];
+    let mut prev_end = None;
+    for token in options.reader.lexer(src) {
+        let token = token?;
+        let gap = &src[prev_end.unwrap_or(0)..token.span.start];
+        let newlines = gap.matches('\n').count();
+        let first = prev_end.is_none();
+        prev_end = Some(token.span.end);
+        let frame = stack.last_mut().expect("the top-level frame is never popped");
+
+        if newlines >= 2 && !is_close(token.kind) {
+            if frame.prefixes.is_empty() {
+                frame.items.push(Node::Blank);
+            } else {
+                frame.blank = true;
+            }
+        }
+        match token.kind {
+            TokenKind::LParen => stack.push(Frame::new("(")),
+            TokenKind::LBracket => stack.push(Frame::new("[")),
+            TokenKind::LBrace => stack.push(Frame::new("{")),
+            TokenKind::HashLBrace => stack.push(Frame::new("#{")),
+            TokenKind::HashLBracket => stack.push(Frame::new("#[")),
+            TokenKind::HashU8LParen => stack.push(Frame::new("#u8(")),
+            TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace => {
+                let mut done = stack.pop().expect("the reader checked that delimiters balance");
+                done.flush_comments();
+                let close = match token.kind {
+                    TokenKind::RParen => ")",
+                    TokenKind::RBracket => "]",
+                    _ => "}",
+                };
+                let node = Node::seq(done.open, done.items, close, done.open == "{");
+                stack.last_mut().expect("the reader checked that delimiters balance").push(node);
+            }
+            TokenKind::Quote => frame.prefixes.push("'".into()),
+            TokenKind::Quasiquote => frame.prefixes.push("`".into()),
+            TokenKind::Unquote => frame.prefixes.push(if token.text == "~" { "~" } else { "," }.into()),
+            TokenKind::UnquoteSplicing => frame.prefixes.push(if token.text == "~@" { "~@" } else { ",@" }.into()),
+            TokenKind::DatumComment => frame.prefixes.push("#;".into()),
+            // A block comment goes with the element after it, so it does not
+            // count as one of the arguments of a form
+            TokenKind::BlockComment => frame.prefixes.push(format!("{} ", token.text).into()),
+            TokenKind::LineComment => {
+                // Keep a block comment before a line comment in source order
+                if frame.prefixes.iter().all(|prefix| prefix.starts_with("#|")) {
+                    let blank = std::mem::take(&mut frame.blank);
+                    frame.flush_comments();
+                    if blank {
+                        frame.items.push(Node::Blank);
+                    }
+                }
+                let trailing = !first && newlines == 0;
+                frame.items.push(Node::Comment { text: token.text.trim_end().to_string(), trailing });
+            }
+            TokenKind::Atom => frame.push(Node::atom(token.text.to_string(), true)),
+            TokenKind::Str | TokenKind::Char | TokenKind::Verbatim => {
+                frame.push(Node::atom(token.text.to_string(), false));
+            }
+        }
+    }
+
+    let mut top = stack.pop().expect("the top-level frame is never popped");
+    top.flush_comments();
+    let mut out = Printer::new(&options.pretty).finish_top_level(&top.items);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// A sequence being built from the token stream.
+struct Frame {
+    /// The opening delimiter, empty for the top level
+    open: &'static str,
+    /// The finished elements, comments and blank lines
+    items: Vec<Node>,
+    /// Reader macro prefixes and block comments waiting for the element
+    /// they apply to
+    prefixes: Vec<Cow<'static, str>>,
+    /// A blank line was seen while prefixes were pending
+    blank: bool,
+}
+
+impl Frame {
+    fn new(open: &'static str) -> Self {
+        Frame { open, items: Vec::new(), prefixes: Vec::new(), blank: false }
+    }
+
+    /// Add an element, wrapped in any pending prefixes and preceded by any
+    /// blank line seen among them.
+    fn push(&mut self, node: Node) {
+        if std::mem::take(&mut self.blank) {
+            self.items.push(Node::Blank);
+        }
+        let node = self.prefixes.drain(..).rev().fold(node, |node, prefix| {
+            // `, @x` unquotes the symbol `@x`, so it must not become `,@x`
+            let splices = matches!(&node, Node::Atom { text, .. } if text.starts_with('@'));
+            match &*prefix {
+                "," | "~" if splices => Node::prefix(format!("{} ", prefix), node),
+                _ => Node::prefix(prefix, node),
+            }
+        });
+        self.items.push(node);
+    }
+
+    /// Keep pending block comments as elements of their own, as in
+    /// `(a #| c |#)` where no element follows them.
+    ///
+    /// Only block comments can be pending at a closing delimiter, since the
+    /// reader checked that every other prefix has its element.
+    fn flush_comments(&mut self) {
+        self.blank = false;
+        let comments = self.prefixes.drain(..).map(|comment| Node::atom(comment.trim_end().to_string(), false));
+        self.items.extend(comments);
+    }
+}
+
+/// Returns `true` for tokens that close a list, vector, map or set.
+fn is_close(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Dialect;
+
+    fn format_width(src: &str, width: usize) -> String {
+        let options = FormatOptions::new().reader(ReaderOptions::new()).pretty(PrettyOptions::new().width(width));
+        let formatted = format(src, &options).unwrap();
+        assert_eq!(format(&formatted, &options).unwrap(), formatted, "not idempotent");
+        formatted
+    }
+
+    #[test]
+    fn format_test() {
+        let src = "\
+;;; Header
+
+
+(define (f x)   ; entry
+  #| block |# (g   x)
+
+
+  ;; between
+  (h #x1F '(a . b) #;(dropped) `(,x ,@y)))
+(f 1)   (f 2)";
+        assert_eq!(format_width(src, 80), "\
+;;; Header
+
+(define (f x) ; entry
+  #| block |# (g x)
+
+  ;; between
+  (h #x1F '(a . b) #;(dropped) `(,x ,@y)))
+(f 1)
+(f 2)
+");
+
+        assert_eq!(format_width("(list ; first\n a b)", 80), "(list ; first\n      a\n      b)\n");
+        assert_eq!(format_width("(a b ; last\n)", 80), "(a b ; last\n   )\n");
+        assert_eq!(format_width("{:a 1\n\n :b [x y z]}", 12), "{:a 1\n\n :b [x y z]}\n");
+        assert_eq!(format_width("(a #| end |#)", 80), "(a #| end |#)\n");
+        assert_eq!(format_width("(f #| c |# ; note\n x)", 80), "(f #| c |# ; note\n   x)\n");
+        assert_eq!(format_width("", 80), "");
+        assert_eq!(format_width("; only\n", 80), "; only\n");
+        assert_eq!(format_width("(a , @x ,@y)", 80), "(a , @x ,@y)\n");
+        assert_eq!(format_width("(a\n #| c |#\n\n b)", 80), "(a\n\n   #| c |# b)\n");
+        assert_eq!(format_width("(a #| c |#\n\n ; note\n b)", 80), "(a #| c |#\n\n   ; note\n   b)\n");
+    }
+
+    #[test]
+    fn block_comment_test() {
+        // A block comment is not one of the bindings of the let
+        let src = "(let #| c |# ((a 1)) (body-one alpha) (body-two beta))";
+        assert_eq!(format_width(src, 20), "\
+(let #| c |# ((a 1))
+  (body-one alpha)
+  (body-two beta))
+");
+        let src = "(let ((a 1)) (body-one alpha) (body-two beta))";
+        assert_eq!(format_width(src, 20), "(let ((a 1))\n  (body-one alpha)\n  (body-two beta))\n");
+    }
+
+    #[test]
+    fn dialect_and_error_test() {
+        let options = FormatOptions::new().reader(ReaderOptions::new().dialect(Dialect::Clojure));
+        assert_eq!(format("`(a, ~b)", &options).unwrap(), "`(a ~b)\n");
+        assert_eq!(format("`(a ~ @b)", &options).unwrap(), "`(a ~ @b)\n");
+        let options = FormatOptions::new().reader(ReaderOptions::new());
+        assert!(matches!(format("(a]", &options), Err(ParseError::MismatchedBracket { .. })));
+        assert!(matches!(format("(a \"\\q\")", &FormatOptions::new()), Err(ParseError::InvalidEscape { .. })));
+    }
+}
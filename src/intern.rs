@@ -0,0 +1,271 @@
+//! Symbol Interning Module
+//!
+//! This module provides [`InternedSymbol`], an [`OwnedSymbol`] that is a
+//! `u32` id into a per-thread [`SymbolTable`]. Every occurrence of a name
+//! shares one stored string, and symbols compare and hash as integers.
+//!
+//! [`Expression::to_owned`](crate::Expression::to_owned) creates symbols one
+//! at a time with [`OwnedSymbol::from_str`].
+//! [`Expression::to_owned_with`](crate::Expression::to_owned_with) instead
+//! takes any [`SymbolInterner`], so a whole tree can be interned through one
+//! table borrow, or through an interner of your own.
+//!
+//! # Features
+//!
+//! - **O(1) comparison**: `InternedSymbol` is `Copy` and compares by id
+//! - **Shared storage**: Each distinct name is stored once per thread
+//! - **Pluggable**: Implement [`SymbolInterner`] to build any symbol type
+//!   with context, such as a compiler's own symbol table
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read, InternedSymbol, OwnedExpression, SymbolTable};
+//!
+//! let expr = read("(let ((x 1)) (+ x x))").unwrap();
+//! let owned: OwnedExpression<InternedSymbol> = SymbolTable::with(|table| expr.to_owned_with(table));
+//! assert_eq!(owned.to_string(), "(let ((x 1)) (+ x x))");
+//!
+//! let x = InternedSymbol::new("x");
+//! assert_eq!(x, InternedSymbol::new("x"));
+//! assert_eq!(&*x.name(), "x");
+//! ```
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::reader::{write_symbol, OwnedSymbol};
+
+/// Builds owned symbols from names, with whatever context it needs.
+///
+/// This is the construction path used by
+/// [`Expression::to_owned_with`](crate::Expression::to_owned_with).
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read, StringOwnedSymbol, OwnedSymbol, SymbolInterner};
+///
+/// /// Counts how many symbols a conversion creates.
+/// struct Counting(usize);
+///
+/// impl SymbolInterner for Counting {
+///     type Symbol = StringOwnedSymbol;
+///
+///     fn intern(&mut self, name: &str) -> StringOwnedSymbol {
+///         self.0 += 1;
+///         StringOwnedSymbol::from_str(name)
+///     }
+/// }
+///
+/// let mut counting = Counting(0);
+/// read("(a :b \"c\" (d))").unwrap().to_owned_with(&mut counting);
+/// assert_eq!(counting.0, 3);
+/// ```
+pub trait SymbolInterner {
+    /// The symbol type this interner creates.
+    type Symbol: OwnedSymbol;
+
+    /// Create the symbol for `name`, which may be a symbol or keyword name.
+    fn intern(&mut self, name: &str) -> Self::Symbol;
+}
+
+/// A symbol interned in the current thread's [`SymbolTable`].
+///
+/// Equal names on the same thread always get the same id, so comparing and
+/// hashing are integer operations. Symbols are tied to the thread that
+/// interned them and cannot be sent to another thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InternedSymbol {
+    id: u32,
+    /// Ids are only meaningful on the thread whose table issued them
+    _thread: PhantomData<*const ()>,
+}
+
+impl InternedSymbol {
+    /// Intern `name` in the current thread's table.
+    pub fn new(name: &str) -> Self {
+        SymbolTable::with(|table| table.intern(name))
+    }
+
+    /// The id of this symbol in its thread's table.
+    ///
+    /// Ids are dense and assigned in order of first use, starting at 0, so
+    /// they can index side tables.
+    pub fn id(self) -> u32 {
+        self.id
+    }
+
+    /// The name of this symbol.
+    pub fn name(self) -> Rc<str> {
+        SymbolTable::with(|table| table.names[self.id as usize].clone())
+    }
+}
+
+impl OwnedSymbol for InternedSymbol {
+    fn from_str(s: &str) -> Self {
+        InternedSymbol::new(s)
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_symbol(f, &self.name())
+    }
+
+    fn symbol_name(&self) -> Cow<'_, str> {
+        Cow::Owned(self.name().to_string())
+    }
+
+    #[cfg(feature = "serde")]
+    fn serialize_symbol<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_str(&self.name())
+    }
+
+    #[cfg(feature = "serde")]
+    fn deserialize_symbol<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(InternedSymbol::new(&name))
+    }
+}
+
+impl fmt::Display for InternedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display(f)
+    }
+}
+
+thread_local! {
+    static SYMBOLS: RefCell<SymbolTable> = RefCell::new(SymbolTable { names: Vec::new(), ids: HashMap::new() });
+}
+
+/// The names behind the current thread's [`InternedSymbol`]s.
+///
+/// Each thread has one table, reached through [`SymbolTable::with`]. Names
+/// are never removed, so ids stay valid for the life of the thread.
+#[derive(Debug)]
+pub struct SymbolTable {
+    /// The name of each id
+    names: Vec<Rc<str>>,
+    /// The id of each name
+    ids: HashMap<Rc<str>, u32>,
+}
+
+impl SymbolTable {
+    /// Run `f` with the current thread's table.
+    ///
+    /// Passing the table to [`Expression::to_owned_with`] interns a whole
+    /// tree with a single lookup of the thread-local.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from inside `f`, which includes creating or printing
+    /// an [`InternedSymbol`] there; use the `table` argument instead.
+    ///
+    /// [`Expression::to_owned_with`]: crate::Expression::to_owned_with
+    pub fn with<R>(f: impl FnOnce(&mut SymbolTable) -> R) -> R {
+        SYMBOLS.with(|table| f(&mut table.try_borrow_mut().expect("SymbolTable::with called inside SymbolTable::with")))
+    }
+
+    /// Intern `name`, returning the existing symbol if it was seen before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table already holds `u32::MAX` names.
+    pub fn intern(&mut self, name: &str) -> InternedSymbol {
+        let id = match self.ids.get(name) {
+            Some(&id) => id,
+            None => {
+                let id = u32::try_from(self.names.len()).expect("symbol table is full");
+                let name: Rc<str> = Rc::from(name);
+                self.names.push(name.clone());
+                self.ids.insert(name, id);
+                id
+            }
+        };
+        InternedSymbol { id, _thread: PhantomData }
+    }
+
+    /// The name of `symbol`.
+    pub fn resolve(&self, symbol: InternedSymbol) -> &str {
+        &self.names[symbol.id as usize]
+    }
+
+    /// The symbol for `name`, if it has been interned.
+    pub fn get(&self, name: &str) -> Option<InternedSymbol> {
+        self.ids.get(name).map(|&id| InternedSymbol { id, _thread: PhantomData })
+    }
+
+    /// The number of distinct names interned.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if nothing has been interned on this thread.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl SymbolInterner for SymbolTable {
+    type Symbol = InternedSymbol;
+
+    fn intern(&mut self, name: &str) -> InternedSymbol {
+        SymbolTable::intern(self, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{read, Expression, OwnedExpression};
+
+    #[test]
+    fn intern_test() {
+        let a = InternedSymbol::new("alpha");
+        let b = InternedSymbol::new("beta");
+        assert_eq!(a, InternedSymbol::new("alpha"));
+        assert_ne!(a, b);
+        assert_eq!(b.id(), a.id() + 1);
+        assert_eq!(&*b.name(), "beta");
+        assert_eq!(InternedSymbol::new("a b").to_string(), "|a b|");
+
+        SymbolTable::with(|table| {
+            assert_eq!(table.get("alpha"), Some(a));
+            assert_eq!(table.get("gamma"), None);
+            assert_eq!(table.resolve(b), "beta");
+            assert_eq!(table.len(), 3);
+        });
+    }
+
+    #[test]
+    fn to_owned_with_test() {
+        let expr = read("(define (f x) (g x :x))").unwrap();
+        let owned: OwnedExpression<InternedSymbol> = SymbolTable::with(|table| expr.to_owned_with(table));
+        assert_eq!(owned, expr.to_owned());
+        assert_eq!(owned.to_string(), "(define (f x) (g x :x))");
+
+        let x = InternedSymbol::new("x");
+        let symbols = match &owned {
+            OwnedExpression::List(items) => items.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(symbols[1], OwnedExpression::List(vec![
+            OwnedExpression::Symbol(InternedSymbol::new("f")),
+            OwnedExpression::Symbol(x),
+        ]));
+        assert_eq!(SymbolTable::with(|table| table.len()), 4);
+        assert_eq!(Expression::Keyword("x".into()).to_owned::<InternedSymbol>(), OwnedExpression::Keyword(x));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let owned: OwnedExpression<InternedSymbol> = read("(|a b| |1| || :|k w| plain)").unwrap().to_owned();
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(json, r#"{"List":[{"Symbol":"a b"},{"Symbol":"1"},{"Symbol":""},{"Keyword":"k w"},{"Symbol":"plain"}]}"#);
+        assert_eq!(serde_json::from_str::<OwnedExpression<InternedSymbol>>(&json).unwrap(), owned);
+    }
+}
@@ -0,0 +1,560 @@
+//! JSON Conversion Module
+//!
+//! This module converts between expressions and [`serde_json::Value`]. It is
+//! available with the `json` feature.
+//!
+//! # Mapping
+//!
+//! | Expression | JSON | Reads back as |
+//! |------------|------|---------------|
+//! | `Null` | `null` | `Null` |
+//! | `Bool` | `true` / `false` | `Bool` |
+//! | `Integer`, `Float` | number (NaN and infinities are rejected) | `Integer`, `Float` |
+//! | `BigInt` (`bignum` feature) | number, if it fits in `u64` | `BigInt` |
+//! | `Str` | string | `Str` |
+//! | `Char` | string | `Str` |
+//! | `Symbol`, `Keyword` | string, or a tagged object (see [`SymbolStyle`]) | `Str`, or `Symbol` and `Keyword` when tagged |
+//! | `List` | array | `List` |
+//! | `Vector`, `Set` | array | `List` |
+//! | `Map`, keyword lists `(:a 1 :b 2)`, alists `((a . 1) (b . 2))` | object (keys must be distinct) | the [`ObjectStyle`]; an empty object is always an empty `Map` |
+//! | `Bytes` | array of numbers | `List` of integers |
+//! | `Quoted` | array in list form, `["quote", x]` | `List`, `("quote" x)`, or `(quote x)` when tagged |
+//!
+//! The conversion is lossless for expressions made only of the forms that
+//! read back as themselves under the chosen options; the last column shows
+//! what every other form turns into.
+//!
+//! Ratios, other dotted lists and map keys that are not strings, symbols or
+//! keywords have no JSON representation and fail with an [`EncodeError`].
+//! So do objects with a repeated key, including keys that differ only in
+//! kind, like `{"a" 1 :a 2}`, since a JSON object holds each key once.
+//! Going the other way, JSON objects read as maps, keyword lists or alists
+//! (see [`ObjectStyle`]), with their keys in the order of
+//! [`serde_json::Map`], which is sorted unless serde_json's
+//! `preserve_order` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde_json::json;
+//! use sexpression::{read, OwnedExpression};
+//!
+//! let expr: OwnedExpression = read("(:name \"web\" :ports (80 443) :tls true)").unwrap().to_owned();
+//! let value = serde_json::Value::try_from(expr).unwrap();
+//! assert_eq!(value, json!({ "name": "web", "ports": [80, 443], "tls": true }));
+//!
+//! let back = OwnedExpression::<sexpression::StringOwnedSymbol>::from(json!([1, "two", null]));
+//! assert_eq!(back.to_string(), "(1 \"two\" null)");
+//! ```
+
+use std::borrow::Cow;
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::EncodeError;
+use crate::reader::{Expression, OwnedExpression, OwnedSymbol};
+
+/// How symbols and keywords are represented in JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SymbolStyle {
+    /// As plain strings, which read back as strings
+    #[default]
+    String,
+    /// As `{"$symbol": "name"}` and `{"$keyword": "name"}` objects, which
+    /// read back as symbols and keywords
+    Tagged,
+}
+
+/// What JSON objects read as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ObjectStyle {
+    /// A map with string keys, `{"a" 1}`
+    #[default]
+    Map,
+    /// A keyword list, `(:a 1)`
+    KeywordList,
+    /// An association list of symbols, `((a . 1))`
+    Alist,
+}
+
+/// Configuration for converting between expressions and JSON.
+///
+/// The defaults are what the `TryFrom` and `From` conversions use. Reading
+/// back JSON written with the same options gives the original expression
+/// whenever the expression only uses the forms those options describe.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    pub(crate) symbols: SymbolStyle,
+    pub(crate) objects: ObjectStyle,
+}
+
+impl JsonOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how symbols and keywords are represented.
+    pub fn symbols(mut self, style: SymbolStyle) -> Self {
+        self.symbols = style;
+        self
+    }
+
+    /// Set what JSON objects read as.
+    pub fn objects(mut self, style: ObjectStyle) -> Self {
+        self.objects = style;
+        self
+    }
+}
+
+impl<'a> Expression<'a> {
+    /// Convert this expression to a JSON value.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - How symbols and keywords are represented
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values JSON cannot represent, such as
+    /// ratios, dotted lists, a `NaN` float or an object with a repeated key
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use sexpression::{read, JsonOptions, SymbolStyle};
+    ///
+    /// let options = JsonOptions::new().symbols(SymbolStyle::Tagged);
+    /// let value = read("((a . 1) (b . x))").unwrap().to_json(&options).unwrap();
+    /// assert_eq!(value, json!({ "a": 1, "b": { "$symbol": "x" } }));
+    /// ```
+    pub fn to_json(&self, options: &JsonOptions) -> Result<Value, EncodeError> {
+        let unsupported = |kind| EncodeError { kind, encoding: "JSON" };
+        Ok(match self {
+            Expression::Null => Value::Null,
+            Expression::Bool(b) => Value::Bool(*b),
+            Expression::Integer(n) => Value::from(*n),
+            Expression::Float(n) => Value::Number(Number::from_f64(*n).ok_or(unsupported("non-finite float"))?),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => Value::from(u64::try_from(n).map_err(|_| unsupported("big integer"))?),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(_) => return Err(unsupported("ratio")),
+            Expression::Str(s) => Value::String(s.to_string()),
+            Expression::Char(c) => Value::String(c.to_string()),
+            Expression::Symbol(name) => symbol_json(name, "$symbol", options),
+            Expression::Keyword(name) => symbol_json(name, "$keyword", options),
+            Expression::List(items) if is_keyword_list(items) => {
+                let pairs = items.chunks(2).map(|pair| (&pair[0], &pair[1]));
+                Value::Object(object(pairs, |value| value.to_json(options))?)
+            }
+            Expression::List(items) if is_alist(items) => {
+                let pairs = items.iter().map(|item| match item {
+                    Expression::DottedList(head, tail) => (&head[0], tail.as_ref()),
+                    _ => unreachable!("alist entries are dotted pairs"),
+                });
+                Value::Object(object(pairs, |value| value.to_json(options))?)
+            }
+            Expression::List(items) | Expression::Vector(items) | Expression::Set(items) => {
+                Value::Array(items.iter().map(|item| item.to_json(options)).collect::<Result<_, _>>()?)
+            }
+            Expression::DottedList(..) => return Err(unsupported("dotted list")),
+            Expression::Hinted(..) => return Err(unsupported("display hint")),
+            Expression::Map(entries) => {
+                Value::Object(object(entries.iter().map(|(key, value)| (key, value)), |value| value.to_json(options))?)
+            }
+            Expression::Bytes(bytes) => Value::Array(bytes.iter().map(|&b| Value::from(b)).collect()),
+            Expression::Quoted(kind, inner) => {
+                Value::Array(vec![symbol_json(kind.symbol(), "$symbol", options), inner.to_json(options)?])
+            }
+        })
+    }
+}
+
+impl<S: OwnedSymbol> OwnedExpression<S> {
+    /// Convert this expression to a JSON value.
+    ///
+    /// Symbol and keyword names come from [`OwnedSymbol::symbol_name`]. See
+    /// [`Expression::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values JSON cannot represent
+    pub fn to_json(&self, options: &JsonOptions) -> Result<Value, EncodeError> {
+        let unsupported = |kind| EncodeError { kind, encoding: "JSON" };
+        Ok(match self {
+            OwnedExpression::Null => Value::Null,
+            OwnedExpression::Bool(b) => Value::Bool(*b),
+            OwnedExpression::Integer(n) => Value::from(*n),
+            OwnedExpression::Float(n) => Value::Number(Number::from_f64(*n).ok_or(unsupported("non-finite float"))?),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::BigInt(n) => Value::from(u64::try_from(n).map_err(|_| unsupported("big integer"))?),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::Ratio(_) => return Err(unsupported("ratio")),
+            OwnedExpression::Str(s) => Value::String(s.clone()),
+            OwnedExpression::Char(c) => Value::String(c.to_string()),
+            OwnedExpression::Symbol(symbol) => symbol_json(&symbol.symbol_name(), "$symbol", options),
+            OwnedExpression::Keyword(symbol) => symbol_json(&symbol.symbol_name(), "$keyword", options),
+            OwnedExpression::List(items) if is_keyword_list(items) => {
+                let pairs = items.chunks(2).map(|pair| (&pair[0], &pair[1]));
+                Value::Object(object(pairs, |value| value.to_json(options))?)
+            }
+            OwnedExpression::List(items) if is_alist(items) => {
+                let pairs = items.iter().map(|item| match item {
+                    OwnedExpression::DottedList(head, tail) => (&head[0], tail.as_ref()),
+                    _ => unreachable!("alist entries are dotted pairs"),
+                });
+                Value::Object(object(pairs, |value| value.to_json(options))?)
+            }
+            OwnedExpression::List(items) | OwnedExpression::Vector(items) | OwnedExpression::Set(items) => {
+                Value::Array(items.iter().map(|item| item.to_json(options)).collect::<Result<_, _>>()?)
+            }
+            OwnedExpression::DottedList(..) => return Err(unsupported("dotted list")),
+            OwnedExpression::Hinted(..) => return Err(unsupported("display hint")),
+            OwnedExpression::Map(entries) => {
+                Value::Object(object(entries.iter().map(|(key, value)| (key, value)), |value| value.to_json(options))?)
+            }
+            OwnedExpression::Bytes(bytes) => Value::Array(bytes.iter().map(|&b| Value::from(b)).collect()),
+            OwnedExpression::Quoted(kind, inner) => {
+                Value::Array(vec![symbol_json(kind.symbol(), "$symbol", options), inner.to_json(options)?])
+            }
+        })
+    }
+
+    /// Convert this expression to a JSON value, moving its strings into it.
+    fn into_json(self, options: &JsonOptions) -> Result<Value, EncodeError> {
+        let unsupported = |kind| EncodeError { kind, encoding: "JSON" };
+        Ok(match self {
+            OwnedExpression::Str(s) => Value::String(s),
+            OwnedExpression::List(items) if is_keyword_list(&items) => {
+                let mut items = items.into_iter();
+                let pairs = std::iter::from_fn(|| Some((items.next()?, items.next()?)));
+                Value::Object(object(pairs, |value| value.into_json(options))?)
+            }
+            OwnedExpression::List(items) if is_alist(&items) => {
+                let pairs = items.into_iter().map(|item| match item {
+                    OwnedExpression::DottedList(mut head, tail) => (head.remove(0), *tail),
+                    _ => unreachable!("alist entries are dotted pairs"),
+                });
+                Value::Object(object(pairs, |value| value.into_json(options))?)
+            }
+            OwnedExpression::List(items) | OwnedExpression::Vector(items) | OwnedExpression::Set(items) => {
+                Value::Array(items.into_iter().map(|item| item.into_json(options)).collect::<Result<_, _>>()?)
+            }
+            OwnedExpression::Map(entries) => Value::Object(object(entries.into_iter(), |value| value.into_json(options))?),
+            OwnedExpression::Bytes(bytes) => Value::Array(bytes.into_iter().map(Value::from).collect()),
+            OwnedExpression::Quoted(kind, inner) => {
+                Value::Array(vec![symbol_json(kind.symbol(), "$symbol", options), inner.into_json(options)?])
+            }
+            OwnedExpression::DottedList(..) => return Err(unsupported("dotted list")),
+            other => other.to_json(options)?,
+        })
+    }
+
+    /// Convert a JSON value to an expression.
+    ///
+    /// Integers that fit in `i64` read as integers, larger ones as big
+    /// integers with the `bignum` feature and as floats without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value
+    /// * `options` - What objects read as, and whether tagged objects read
+    ///   as symbols and keywords. An empty object always reads as an empty
+    ///   map, which writes back as an object.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use sexpression::{JsonOptions, ObjectStyle, OwnedExpression, StringOwnedSymbol};
+    ///
+    /// let options = JsonOptions::new().objects(ObjectStyle::KeywordList);
+    /// let expr = OwnedExpression::<StringOwnedSymbol>::from_json(json!({ "port": 80 }), &options);
+    /// assert_eq!(expr.to_string(), "(:port 80)");
+    /// ```
+    pub fn from_json(value: Value, options: &JsonOptions) -> Self {
+        match value {
+            Value::Null => OwnedExpression::Null,
+            Value::Bool(b) => OwnedExpression::Bool(b),
+            Value::Number(n) => number(&n),
+            Value::String(s) => OwnedExpression::Str(s),
+            Value::Array(items) => {
+                OwnedExpression::List(items.into_iter().map(|item| Self::from_json(item, options)).collect())
+            }
+            Value::Object(entries) => {
+                if let Some(expr) = tagged_symbol(&entries, options) {
+                    return expr;
+                }
+                if entries.is_empty() {
+                    return OwnedExpression::Map(Vec::new());
+                }
+                let entries = entries.into_iter().map(|(key, value)| (key, Self::from_json(value, options)));
+                match options.objects {
+                    ObjectStyle::Map => OwnedExpression::Map(
+                        entries.map(|(key, value)| (OwnedExpression::Str(key), value)).collect()
+                    ),
+                    ObjectStyle::KeywordList => OwnedExpression::List(
+                        entries.flat_map(|(key, value)| [OwnedExpression::Keyword(S::from_str(&key)), value]).collect()
+                    ),
+                    ObjectStyle::Alist => OwnedExpression::List(
+                        entries
+                            .map(|(key, value)| {
+                                OwnedExpression::DottedList(vec![OwnedExpression::Symbol(S::from_str(&key))], Box::new(value))
+                            })
+                            .collect()
+                    ),
+                }
+            }
+        }
+    }
+}
+
+impl<S: OwnedSymbol> TryFrom<OwnedExpression<S>> for Value {
+    type Error = EncodeError;
+
+    /// Convert with the default [`JsonOptions`], moving strings out of the
+    /// expression.
+    fn try_from(expr: OwnedExpression<S>) -> Result<Self, EncodeError> {
+        expr.into_json(&JsonOptions::default())
+    }
+}
+
+impl<S: OwnedSymbol> From<Value> for OwnedExpression<S> {
+    /// Convert with the default [`JsonOptions`].
+    fn from(value: Value) -> Self {
+        OwnedExpression::from_json(value, &JsonOptions::default())
+    }
+}
+
+/// A symbol or keyword name as JSON, tagged with `tag` if requested.
+fn symbol_json(name: &str, tag: &str, options: &JsonOptions) -> Value {
+    match options.symbols {
+        SymbolStyle::String => Value::String(name.to_string()),
+        SymbolStyle::Tagged => Value::Object(Map::from_iter([(tag.to_string(), Value::String(name.to_string()))])),
+    }
+}
+
+/// The symbol or keyword a `{"$symbol": name}` object stands for, if
+/// tagged symbols are enabled.
+fn tagged_symbol<S: OwnedSymbol>(entries: &Map<String, Value>, options: &JsonOptions) -> Option<OwnedExpression<S>> {
+    if options.symbols != SymbolStyle::Tagged || entries.len() != 1 {
+        return None;
+    }
+    match entries.iter().next() {
+        Some((tag, Value::String(name))) if tag == "$symbol" => Some(OwnedExpression::Symbol(S::from_str(name))),
+        Some((tag, Value::String(name))) if tag == "$keyword" => Some(OwnedExpression::Keyword(S::from_str(name))),
+        _ => None,
+    }
+}
+
+/// A JSON number as the closest expression.
+fn number<S: OwnedSymbol>(n: &Number) -> OwnedExpression<S> {
+    if let Some(n) = n.as_i64() {
+        return OwnedExpression::Integer(n);
+    }
+    #[cfg(feature = "bignum")]
+    if let Some(n) = n.as_u64() {
+        return OwnedExpression::BigInt(n.into());
+    }
+    OwnedExpression::Float(n.as_f64().unwrap_or(f64::NAN))
+}
+
+/// Build a JSON object from key-value pairs, converting each value with
+/// `convert`.
+///
+/// Keys that name the same string, such as `"a"` and `:a`, are an error
+/// rather than letting the last value win.
+fn object<N: JsonNode, V>(
+    pairs: impl Iterator<Item = (N, V)>,
+    mut convert: impl FnMut(V) -> Result<Value, EncodeError>,
+) -> Result<Map<String, Value>, EncodeError> {
+    let mut map = Map::new();
+    for (key, value) in pairs {
+        let name = match key.key_name() {
+            Some(name) => name,
+            None => return Err(EncodeError { kind: "non-string map key", encoding: "JSON" }),
+        };
+        if map.contains_key(name.as_ref()) {
+            return Err(EncodeError { kind: "duplicate object key", encoding: "JSON" });
+        }
+        map.insert(name.into_owned(), convert(value)?);
+    }
+    Ok(map)
+}
+
+/// What the object forms need to know about an expression, borrowed or
+/// owned.
+trait JsonNode {
+    /// The name of a string, symbol or keyword
+    fn key_name(&self) -> Option<Cow<'_, str>>;
+
+    /// Returns `true` for a keyword.
+    fn is_keyword(&self) -> bool;
+
+    /// Returns `true` for an alist entry `(k . v)` with a symbol or string
+    /// key.
+    fn is_entry(&self) -> bool;
+}
+
+impl JsonNode for Expression<'_> {
+    fn key_name(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Expression::Str(name) | Expression::Symbol(name) | Expression::Keyword(name) => Some(Cow::Borrowed(name)),
+            _ => None,
+        }
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(self, Expression::Keyword(_))
+    }
+
+    fn is_entry(&self) -> bool {
+        matches!(self, Expression::DottedList(head, _)
+            if head.len() == 1 && matches!(head[0], Expression::Symbol(_) | Expression::Str(_)))
+    }
+}
+
+impl<S: OwnedSymbol> JsonNode for OwnedExpression<S> {
+    fn key_name(&self) -> Option<Cow<'_, str>> {
+        match self {
+            OwnedExpression::Str(name) => Some(Cow::Borrowed(name)),
+            OwnedExpression::Symbol(symbol) | OwnedExpression::Keyword(symbol) => Some(symbol.symbol_name()),
+            _ => None,
+        }
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(self, OwnedExpression::Keyword(_))
+    }
+
+    fn is_entry(&self) -> bool {
+        matches!(self, OwnedExpression::DottedList(head, _)
+            if head.len() == 1 && matches!(head[0], OwnedExpression::Symbol(_) | OwnedExpression::Str(_)))
+    }
+}
+
+impl<N: JsonNode> JsonNode for &N {
+    fn key_name(&self) -> Option<Cow<'_, str>> {
+        (**self).key_name()
+    }
+
+    fn is_keyword(&self) -> bool {
+        (**self).is_keyword()
+    }
+
+    fn is_entry(&self) -> bool {
+        (**self).is_entry()
+    }
+}
+
+/// Returns `true` for a non-empty keyword list `(:k v ...)`.
+fn is_keyword_list<N: JsonNode>(items: &[N]) -> bool {
+    !items.is_empty() && items.len().is_multiple_of(2) && items.iter().step_by(2).all(N::is_keyword)
+}
+
+/// Returns `true` for a non-empty alist `((k . v) ...)` with symbol or
+/// string keys.
+fn is_alist<N: JsonNode>(items: &[N]) -> bool {
+    !items.is_empty() && items.iter().all(N::is_entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::InternedSymbol;
+    use crate::reader::{read, StringOwnedSymbol};
+    use serde_json::json;
+
+    type Owned = OwnedExpression<StringOwnedSymbol>;
+
+    fn to_json(src: &str, options: &JsonOptions) -> Result<Value, EncodeError> {
+        read(src).unwrap().to_json(options)
+    }
+
+    #[test]
+    fn to_json_test() {
+        let options = JsonOptions::new();
+        assert_eq!(
+            to_json("(1 -2.5 true null \"s\" #\\c sym :kw [a] #{b} #u8(1 2) 'q ())", &options).unwrap(),
+            json!([1, -2.5, true, null, "s", "c", "sym", "kw", ["a"], ["b"], [1, 2], ["quote", "q"], []]),
+        );
+        assert_eq!(to_json("{\"a\" 1 b 2 :c 3}", &options).unwrap(), json!({ "a": 1, "b": 2, "c": 3 }));
+        assert_eq!(to_json("(:a (:b 1))", &options).unwrap(), json!({ "a": { "b": 1 } }));
+        assert_eq!(to_json("((a . 1) (\"b\" . (x y)))", &options).unwrap(), json!({ "a": 1, "b": ["x", "y"] }));
+        assert_eq!(to_json("(:a)", &options).unwrap(), json!(["a"]));
+
+        let err = |src| to_json(src, &options).unwrap_err().kind;
+        assert_eq!(err("(a . b)"), "dotted list");
+        assert_eq!(err("{1 2}"), "non-string map key");
+        assert_eq!(err("(:a 1 :a 2)"), "duplicate object key");
+        assert_eq!(err("{\"a\" 1 :a 2}"), "duplicate object key");
+        assert_eq!(err("((a . 1) (\"a\" . 2))"), "duplicate object key");
+        assert_eq!(err("+nan.0"), "non-finite float");
+        assert_eq!(to_json("+inf.0", &options).unwrap_err().to_string(), "non-finite float cannot be written in JSON form");
+    }
+
+    #[test]
+    fn from_json_test() {
+        let expr = Owned::from(json!({ "name": "web", "ports": [80, 443], "ratio": 0.5, "none": null }));
+        assert_eq!(expr.to_string(), "{\"name\" \"web\" \"none\" null \"ports\" (80 443) \"ratio\" 0.5}");
+
+        let options = JsonOptions::new().objects(ObjectStyle::Alist);
+        assert_eq!(Owned::from_json(json!({ "a": [true] }), &options).to_string(), "((a . (true)))");
+        #[cfg(feature = "bignum")]
+        assert_eq!(Owned::from(json!(u64::MAX)).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let tagged = JsonOptions::new().symbols(SymbolStyle::Tagged).objects(ObjectStyle::KeywordList);
+        for src in ["(define (f x) (:body (+ x 1.5) :doc \"f\"))", "((1 2) (a b) null false)"] {
+            let expr: Owned = read(src).unwrap().to_owned();
+            let value = expr.to_json(&tagged).unwrap();
+            assert_eq!(Owned::from_json(value, &tagged), expr);
+        }
+        let value = to_json("(x :y)", &JsonOptions::new().symbols(SymbolStyle::Tagged)).unwrap();
+        assert_eq!(value, json!([{ "$symbol": "x" }, { "$keyword": "y" }]));
+
+        let plain: Owned = read("(\"a\" 1 [2.0 -3])").unwrap().to_owned();
+        let value = Value::try_from(plain).unwrap();
+        assert_eq!(Owned::from(value), read("(\"a\" 1 (2.0 -3))").unwrap().to_owned());
+    }
+
+    #[test]
+    fn symbol_type_test() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Plain(String);
+
+        impl OwnedSymbol for Plain {
+            fn from_str(s: &str) -> Self {
+                Plain(s.to_string())
+            }
+
+            fn display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        let tagged = JsonOptions::new().symbols(SymbolStyle::Tagged).objects(ObjectStyle::KeywordList);
+        let expr = read("(f \"s\" (:a x :b ((c . 1))) #u8(7) 'q)").unwrap();
+        let expected = expr.to_json(&tagged).unwrap();
+        let interned: OwnedExpression<InternedSymbol> = expr.to_owned();
+        assert_eq!(interned.to_json(&tagged).unwrap(), expected);
+        let plain: OwnedExpression<Plain> = expr.to_owned();
+        assert_eq!(plain.to_json(&tagged).unwrap(), expected);
+        assert_eq!(Value::try_from(plain).unwrap(), expr.to_json(&JsonOptions::new()).unwrap());
+        let interned: OwnedExpression<InternedSymbol> = read("{\"a\" 1 :a 2}").unwrap().to_owned();
+        assert_eq!(Value::try_from(interned).unwrap_err().kind, "duplicate object key");
+    }
+
+    #[test]
+    fn empty_object_test() {
+        for objects in [ObjectStyle::Map, ObjectStyle::KeywordList, ObjectStyle::Alist] {
+            let options = JsonOptions::new().objects(objects);
+            let expr = Owned::from_json(json!({}), &options);
+            assert_eq!(expr, Owned::Map(Vec::new()));
+            assert_eq!(expr.to_json(&options).unwrap(), json!({}));
+        }
+    }
+}
@@ -0,0 +1,841 @@
+//! S-Expression Lexer Module
+//!
+//! This module provides a character-level lexer that turns S-expression source
+//! text into a stream of zero-copy tokens. Unlike a whitespace splitter, the
+//! lexer understands string literals and comments, so whitespace and
+//! parentheses inside `"..."` or `#| ... |#` never break a token.
+//!
+//! # Features
+//!
+//! - **Zero-copy tokens**: Every token borrows its text from the source
+//! - **Source spans**: Every token records its byte range, line and column
+//! - **String literals**: Strings are scanned as a single token, escapes included
+//! - **Escape sequences**: `\"`, `\\`, `\n`, `\r`, `\t`, `\0` and `\u{...}`
+//! - **Quoted symbols**: `|...|` sections make whitespace and delimiters part
+//!   of an atom, as in `|hello world|`
+//! - **Collection delimiters**: `[` `]`, `{` `}` and `#{` for vectors, maps and sets
+//! - **Bytevectors**: `#u8(` opens an R7RS bytevector such as `#u8(1 2 255)`
+//! - **Character literals**: `#\a`, `#\(`, `#\space` and `#\x41` are single tokens
+//! - **Configurable syntax**: Clojure-style `~` unquote with `,` as whitespace,
+//!   and Rivest-style `3:abc` verbatim strings
+//! - **Comments**: `;` line comments, nested `#| ... |#` block comments and
+//!   `#;` datum comment markers are emitted as tokens for the parser to skip
+//! - **Production error handling**: Unterminated strings are reported as [`ParseError`]
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{Lexer, TokenKind};
+//!
+//! let tokens: Vec<_> = Lexer::new("(print \"hello world\")")
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(tokens.len(), 4);
+//! assert_eq!(tokens[2].kind, TokenKind::Str);
+//! assert_eq!(tokens[2].text, "\"hello world\"");
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::error::ParseError;
+use crate::span::Span;
+
+/// The kind of a lexical token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// An opening parenthesis `(`
+    LParen,
+    /// A closing parenthesis `)`
+    RParen,
+    /// An opening bracket `[`, starting a vector
+    LBracket,
+    /// A closing bracket `]`
+    RBracket,
+    /// An opening brace `{`, starting a map
+    LBrace,
+    /// A closing brace `}`, ending a map or set
+    RBrace,
+    /// A `#{` marker, starting a set
+    HashLBrace,
+    /// A `#[` marker, starting a display hint closed by `]`
+    HashLBracket,
+    /// A `#u8(` marker, starting a bytevector closed by `)`
+    HashU8LParen,
+    /// A quote character `'`
+    Quote,
+    /// A quasiquote character `` ` ``
+    Quasiquote,
+    /// An unquote character `,`
+    Unquote,
+    /// An unquote-splicing marker `,@`
+    UnquoteSplicing,
+    /// A string literal, including its surrounding double quotes
+    Str,
+    /// A Rivest-style verbatim string such as `3:abc`, including its length prefix
+    Verbatim,
+    /// A character literal such as `#\a` or `#\space`, including the `#\`
+    Char,
+    /// A `;` comment running to the end of the line (newline excluded)
+    LineComment,
+    /// A `#| ... |#` comment, which may nest
+    BlockComment,
+    /// A `#;` marker that comments out the following expression
+    DatumComment,
+    /// Any other run of non-delimiter characters (numbers, symbols, ...),
+    /// including any `|...|` sections
+    Atom,
+}
+
+/// A single token borrowed from the source string.
+///
+/// The `text` of a token is the exact slice of source it was scanned from.
+/// For [`TokenKind::Str`] tokens this includes the surrounding quotes and any
+/// escape sequences in their raw, unprocessed form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The kind of token
+    pub kind: TokenKind,
+    /// The raw source text of the token
+    pub text: &'a str,
+    /// The location of the token in the source
+    pub span: Span,
+}
+
+/// Character-level S-expression lexer.
+///
+/// The lexer scans the source lazily, producing one [`Token`] per call to
+/// [`Lexer::next_token`]. It also implements [`Iterator`], yielding
+/// `Result<Token, ParseError>` items.
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{Lexer, TokenKind};
+///
+/// let mut lexer = Lexer::new("(a \"b c\")");
+/// assert_eq!(lexer.next_token().unwrap().unwrap().kind, TokenKind::LParen);
+/// assert_eq!(lexer.next_token().unwrap().unwrap().text, "a");
+/// assert_eq!(lexer.next_token().unwrap().unwrap().text, "\"b c\"");
+/// assert_eq!(lexer.next_token().unwrap().unwrap().kind, TokenKind::RParen);
+/// assert!(lexer.next_token().unwrap().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+    brackets: bool,
+    tilde_unquote: bool,
+    char_literals: bool,
+    verbatim_strings: bool,
+    bytevectors: bool,
+    delimiters: Cow<'static, str>,
+}
+
+impl<'a> Lexer<'a> {
+    /// Create a lexer over the given source string.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source string to tokenize
+    pub fn new(src: &'a str) -> Self {
+        Lexer {
+            src,
+            pos: 0,
+            line: 1,
+            column: 1,
+            brackets: false,
+            tilde_unquote: false,
+            char_literals: true,
+            verbatim_strings: false,
+            bytevectors: false,
+            delimiters: Cow::Borrowed(""),
+        }
+    }
+
+    /// Enable or disable the collection delimiters `[`, `]`, `{`, `}` and `#{`.
+    ///
+    /// When disabled they are ordinary atom characters, so `[a]` is a single
+    /// atom. Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::Lexer;
+    ///
+    /// let texts: Vec<_> = Lexer::new("[a]").map(|t| t.unwrap().text).collect();
+    /// assert_eq!(texts, vec!["[a]"]);
+    ///
+    /// let texts: Vec<_> = Lexer::new("[a]").brackets(true).map(|t| t.unwrap().text).collect();
+    /// assert_eq!(texts, vec!["[", "a", "]"]);
+    /// ```
+    pub fn brackets(mut self, enabled: bool) -> Self {
+        self.brackets = enabled;
+        self
+    }
+
+    /// Use Clojure's `~` and `~@` for unquote and treat `,` as whitespace.
+    ///
+    /// Disabled by default, in which case `,` and `,@` are the unquote
+    /// markers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{Lexer, TokenKind};
+    ///
+    /// let kinds: Vec<_> = Lexer::new("a, ~b").tilde_unquote(true).map(|t| t.unwrap().kind).collect();
+    /// assert_eq!(kinds, vec![TokenKind::Atom, TokenKind::Unquote, TokenKind::Atom]);
+    /// ```
+    pub fn tilde_unquote(mut self, enabled: bool) -> Self {
+        self.tilde_unquote = enabled;
+        self
+    }
+
+    /// Enable or disable `#\` character literals.
+    ///
+    /// When disabled, `#\a` is scanned as an ordinary atom. Enabled by
+    /// default.
+    pub fn char_literals(mut self, enabled: bool) -> Self {
+        self.char_literals = enabled;
+        self
+    }
+
+    /// Enable or disable the `#u8(` bytevector marker.
+    ///
+    /// When disabled, `#u8` is scanned as an ordinary atom. Disabled by
+    /// default.
+    pub fn bytevectors(mut self, enabled: bool) -> Self {
+        self.bytevectors = enabled;
+        self
+    }
+
+    /// Enable or disable Rivest-style `len:bytes` verbatim strings.
+    ///
+    /// A verbatim string is a decimal length, a colon and exactly that many
+    /// bytes of raw text, so `5:a b()` is a single token. Disabled by default.
+    pub fn verbatim_strings(mut self, enabled: bool) -> Self {
+        self.verbatim_strings = enabled;
+        self
+    }
+
+    /// Treat each character of `chars` as an extra separator between atoms.
+    ///
+    /// Separators end an atom and are otherwise skipped like whitespace.
+    /// None by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::Lexer;
+    ///
+    /// let texts: Vec<_> = Lexer::new("a=b").delimiters("=").map(|t| t.unwrap().text).collect();
+    /// assert_eq!(texts, vec!["a", "b"]);
+    /// ```
+    pub fn delimiters(mut self, chars: impl Into<Cow<'static, str>>) -> Self {
+        self.delimiters = chars.into();
+        self
+    }
+
+    /// Scan the next token from the source.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(token))` for the next token, `Ok(None)` at end of input
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::UnterminatedString`] or
+    /// [`ParseError::UnterminatedComment`] if a string literal or block
+    /// comment is not closed before the end of input,
+    /// [`ParseError::UnterminatedSymbol`] for an unclosed `|` in an atom, and
+    /// [`ParseError::InvalidVerbatim`] if a verbatim string is cut short
+    pub fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        self.skip_whitespace();
+        let rest = &self.src[self.pos..];
+        let first = match rest.chars().next() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        let (start, line, column) = (self.pos, self.line, self.column);
+        let kind = match first {
+            '(' => { self.advance(1); TokenKind::LParen }
+            ')' => { self.advance(1); TokenKind::RParen }
+            '[' if self.brackets => { self.advance(1); TokenKind::LBracket }
+            ']' if self.brackets => { self.advance(1); TokenKind::RBracket }
+            '{' if self.brackets => { self.advance(1); TokenKind::LBrace }
+            '}' if self.brackets => { self.advance(1); TokenKind::RBrace }
+            '#' if self.brackets && rest.starts_with("#{") => { self.advance(2); TokenKind::HashLBrace }
+            '#' if self.brackets && rest.starts_with("#[") => { self.advance(2); TokenKind::HashLBracket }
+            '#' if self.bytevectors && rest.starts_with("#u8(") => { self.advance(4); TokenKind::HashU8LParen }
+            '\'' => { self.advance(1); TokenKind::Quote }
+            '`' => { self.advance(1); TokenKind::Quasiquote }
+            ',' if rest.starts_with(",@") => { self.advance(2); TokenKind::UnquoteSplicing }
+            ',' => { self.advance(1); TokenKind::Unquote }
+            '~' if self.tilde_unquote && rest.starts_with("~@") => { self.advance(2); TokenKind::UnquoteSplicing }
+            '~' if self.tilde_unquote => { self.advance(1); TokenKind::Unquote }
+            '"' => { self.scan_string()?; TokenKind::Str }
+            ';' => { self.scan_line_comment(); TokenKind::LineComment }
+            '#' if rest.starts_with("#|") => { self.scan_block_comment()?; TokenKind::BlockComment }
+            '#' if rest.starts_with("#;") => { self.advance(2); TokenKind::DatumComment }
+            '#' if self.char_literals && rest.starts_with("#\\") => { self.scan_char(); TokenKind::Char }
+            '0'..='9' if self.verbatim_strings && self.scan_verbatim()? => TokenKind::Verbatim,
+            _ => { self.scan_atom()?; TokenKind::Atom }
+        };
+
+        let span = Span { start, end: self.pos, line, column };
+        Ok(Some(Token { kind, text: &self.src[start..self.pos], span }))
+    }
+
+    /// Advance past any leading whitespace, including commas and extra
+    /// delimiters if they count as whitespace.
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        let commas = self.tilde_unquote;
+        let delimiters = &*self.delimiters;
+        let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || (commas && c == ',') || delimiters.contains(c));
+        self.advance(rest.len() - trimmed.len());
+    }
+
+    /// Move forward by `len` bytes, keeping the line and column up to date.
+    fn advance(&mut self, len: usize) {
+        let consumed = &self.src[self.pos..self.pos + len];
+        match consumed.rfind('\n') {
+            Some(i) => {
+                self.line += consumed.matches('\n').count();
+                self.column = consumed[i + 1..].chars().count() + 1;
+            }
+            None => self.column += consumed.chars().count(),
+        }
+        self.pos += len;
+    }
+
+    /// Scan a string literal starting at the opening quote.
+    ///
+    /// Escape sequences are skipped over but not validated here; that
+    /// happens in [`unescape`] when the literal is turned into a value.
+    fn scan_string(&mut self) -> Result<(), ParseError> {
+        let bytes = self.src.as_bytes();
+        let mut i = self.pos + 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    self.advance(i + 1 - self.pos);
+                    return Ok(());
+                }
+                // Delimiters are ASCII, so skipping a single byte after the
+                // backslash is enough to step over an escaped quote.
+                b'\\' => i += 2,
+                _ => i += 1,
+            }
+        }
+        let span = Span { start: self.pos, end: self.src.len(), line: self.line, column: self.column };
+        Err(ParseError::UnterminatedString { span, token: self.src[self.pos..].to_string() })
+    }
+
+    /// Scan a `;` comment up to, but not including, the end of the line.
+    fn scan_line_comment(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.advance(rest.find('\n').unwrap_or(rest.len()));
+    }
+
+    /// Scan a possibly nested `#| ... |#` comment.
+    fn scan_block_comment(&mut self) -> Result<(), ParseError> {
+        let bytes = self.src.as_bytes();
+        let mut depth = 0;
+        let mut i = self.pos;
+        while i + 1 < bytes.len() {
+            match (bytes[i], bytes[i + 1]) {
+                (b'#', b'|') => { depth += 1; i += 2; }
+                (b'|', b'#') => {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        self.advance(i - self.pos);
+                        return Ok(());
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        let span = Span { start: self.pos, end: self.src.len(), line: self.line, column: self.column };
+        Err(ParseError::UnterminatedComment { span, token: self.src[self.pos..].to_string() })
+    }
+
+    /// An empty span at the lexer's current position.
+    ///
+    /// Once the lexer is exhausted this is the end of the input, which is
+    /// where end-of-input errors are reported.
+    pub fn position(&self) -> Span {
+        Span { start: self.pos, end: self.pos, line: self.line, column: self.column }
+    }
+
+    /// Scan a `len:bytes` verbatim string starting at its first digit.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(false)` without consuming anything if the digits are not followed
+    /// by a colon, so the text is an ordinary atom
+    fn scan_verbatim(&mut self) -> Result<bool, ParseError> {
+        let rest = &self.src[self.pos..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if !rest[digits..].starts_with(':') {
+            return Ok(false);
+        }
+        let end = rest[..digits].parse::<usize>().ok().and_then(|len| len.checked_add(digits + 1));
+        match end {
+            Some(end) if rest.is_char_boundary(end) => {
+                self.advance(end);
+                Ok(true)
+            }
+            _ => {
+                let span = Span { start: self.pos, end: self.src.len(), line: self.line, column: self.column };
+                Err(ParseError::InvalidVerbatim { span })
+            }
+        }
+    }
+
+    /// Scan a `#\` character literal.
+    ///
+    /// The character right after `#\` is always part of the token, even if it
+    /// is whitespace or a delimiter, so `#\(` and `#\ ` are complete literals.
+    /// Any following non-delimiter characters belong to a character name.
+    fn scan_char(&mut self) {
+        self.advance(2);
+        if let Some(c) = self.src[self.pos..].chars().next() {
+            self.advance(c.len_utf8());
+            self.scan_plain();
+        }
+    }
+
+    /// Scan an atom, including any `|...|` sections inside it.
+    ///
+    /// Between bars, whitespace and delimiters are ordinary characters and a
+    /// backslash escapes the next character, so `|a\|b|` is one atom.
+    fn scan_atom(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.scan_plain();
+            if !self.src[self.pos..].starts_with('|') {
+                return Ok(());
+            }
+            let bytes = self.src.as_bytes();
+            let mut i = self.pos + 1;
+            loop {
+                match bytes.get(i) {
+                    Some(b'|') => break,
+                    Some(b'\\') => i += 2,
+                    Some(_) => i += 1,
+                    None => {
+                        let span = Span { start: self.pos, end: self.src.len(), line: self.line, column: self.column };
+                        return Err(ParseError::UnterminatedSymbol { span, token: self.src[self.pos..].to_string() });
+                    }
+                }
+            }
+            self.advance(i + 1 - self.pos);
+        }
+    }
+
+    /// Scan a run of non-delimiter characters, stopping at a `|`.
+    fn scan_plain(&mut self) {
+        let rest = &self.src[self.pos..];
+        let brackets = self.brackets;
+        let delimiters = &*self.delimiters;
+        let len = rest
+            .find(|c: char| {
+                c.is_whitespace() || c == '|' || is_delimiter(c) || (brackets && is_bracket(c)) || delimiters.contains(c)
+            })
+            .unwrap_or(rest.len());
+        self.advance(len);
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
+
+/// Returns `true` for characters that always end an atom.
+fn is_delimiter(c: char) -> bool {
+    matches!(c, '(' | ')' | '\'' | '`' | ',' | '"' | ';')
+}
+
+/// Returns `true` for the collection delimiters, which end an atom when enabled.
+fn is_bracket(c: char) -> bool {
+    matches!(c, '[' | ']' | '{' | '}')
+}
+
+/// Resolve the escape sequences of a string literal token.
+///
+/// This borrows from the source when the literal contains no escapes and only
+/// allocates when at least one escape sequence has to be rewritten.
+///
+/// # Arguments
+///
+/// * `token` - A [`TokenKind::Str`] token, including its surrounding quotes
+///
+/// # Returns
+///
+/// The unescaped string contents
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidEscape`] for unknown or malformed escapes
+pub(crate) fn unescape<'a>(token: &Token<'a>) -> Result<Cow<'a, str>, ParseError> {
+    let raw = &token.text[1..token.text.len() - 1];
+    // Fast path: nothing to rewrite
+    if !raw.contains('\\') {
+        return Ok(Cow::Borrowed(raw));
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match unescape_next(&mut chars, '"') {
+            Some(c) => out.push(c),
+            None => {
+                let end = chars.next().map_or(raw.len(), |(j, _)| j);
+                return Err(invalid_escape(token, 1 + i, 1 + end));
+            }
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
+/// Resolve the `|...|` sections of an atom token into a symbol name.
+///
+/// Text outside the bars is kept as written. Between bars, a backslash
+/// starts the same escapes as in string literals, with `\|` in place of
+/// `\"`. This borrows from the source when the whole atom is a single
+/// `|...|` section without escapes.
+///
+/// # Arguments
+///
+/// * `token` - A [`TokenKind::Atom`] token containing at least one `|`
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidEscape`] for unknown or malformed escapes
+pub(crate) fn unescape_symbol<'a>(token: &Token<'a>) -> Result<Cow<'a, str>, ParseError> {
+    let text = token.text;
+    if let Some(inner) = text.strip_prefix('|').and_then(|rest| rest.strip_suffix('|')) {
+        if !inner.contains(['|', '\\']) {
+            return Ok(Cow::Borrowed(inner));
+        }
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut quoted = false;
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '|' => quoted = !quoted,
+            '\\' if quoted => match unescape_next(&mut chars, '|') {
+                Some(c) => out.push(c),
+                None => {
+                    let end = chars.next().map_or(text.len(), |(j, _)| j);
+                    return Err(invalid_escape(token, i, end));
+                }
+            },
+            _ => out.push(c),
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
+/// Decode the escape sequence following a backslash.
+///
+/// `quote` is the character that closes the literal, which escapes itself.
+fn unescape_next(chars: &mut std::str::CharIndices<'_>, quote: char) -> Option<char> {
+    match chars.next().map(|(_, c)| c) {
+        Some(c) if c == quote || c == '\\' => Some(c),
+        Some('n') => Some('\n'),
+        Some('r') => Some('\r'),
+        Some('t') => Some('\t'),
+        Some('0') => Some('\0'),
+        Some('u') => unescape_unicode(chars),
+        _ => None,
+    }
+}
+
+/// Write `s` between two `quote` characters, escaping it so that it reads
+/// back unchanged.
+///
+/// With `'"'` this writes a string literal and with `'|'` a quoted symbol.
+/// Backslashes, the quote character and control characters are escaped;
+/// everything else is written as is.
+pub(crate) fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str, quote: char) -> fmt::Result {
+    write!(f, "{}", quote)?;
+    let mut plain = 0;
+    for (i, c) in s.char_indices() {
+        if c != quote && c != '\\' && !c.is_control() {
+            continue;
+        }
+        f.write_str(&s[plain..i])?;
+        match c {
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\0' => f.write_str("\\0")?,
+            c if c.is_control() => write!(f, "\\u{{{:X}}}", c as u32)?,
+            c => write!(f, "\\{}", c)?,
+        }
+        plain = i + c.len_utf8();
+    }
+    f.write_str(&s[plain..])?;
+    write!(f, "{}", quote)
+}
+
+/// Decode the `{XXXX}` part of a `\u{XXXX}` escape.
+///
+/// Whenever the braces are present they are consumed from the iterator, even
+/// if the code point between them turns out to be invalid.
+fn unescape_unicode(chars: &mut std::str::CharIndices<'_>) -> Option<char> {
+    let rest = chars.as_str();
+    let (hex, _) = rest.strip_prefix('{')?.split_once('}')?;
+    // Consume `{`, the hex digits and `}`
+    for _ in 0..hex.chars().count() + 2 {
+        chars.next();
+    }
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+/// Build a [`ParseError::InvalidEscape`] for `token.text[start..end]`.
+fn invalid_escape(token: &Token<'_>, start: usize, end: usize) -> ParseError {
+    let prefix = &token.text[..start];
+    let (line, column) = match prefix.rfind('\n') {
+        Some(i) => (
+            token.span.line + prefix.matches('\n').count(),
+            prefix[i + 1..].chars().count() + 1,
+        ),
+        None => (token.span.line, token.span.column + prefix.chars().count()),
+    };
+    let span = Span { start: token.span.start + start, end: token.span.start + end, line, column };
+    ParseError::InvalidEscape { span, sequence: token.text[start..end].to_string() }
+}
+
+/// The contents of a verbatim string token, without its length prefix.
+pub(crate) fn verbatim_contents<'a>(token: &Token<'a>) -> &'a str {
+    token.text.split_once(':').map_or("", |(_, body)| body)
+}
+
+/// Character names accepted after `#\`, as in R7RS.
+const CHAR_NAMES: [(&str, char); 9] = [
+    ("alarm", '\u{7}'),
+    ("backspace", '\u{8}'),
+    ("delete", '\u{7F}'),
+    ("escape", '\u{1B}'),
+    ("newline", '\n'),
+    ("null", '\0'),
+    ("return", '\r'),
+    ("space", ' '),
+    ("tab", '\t'),
+];
+
+/// Decode a character literal token.
+///
+/// A literal is either a single character (`#\a`), a character name
+/// (`#\newline`) or a hexadecimal scalar value (`#\x41`). `#\x` on its own
+/// is the character `x`.
+///
+/// # Arguments
+///
+/// * `token` - A [`TokenKind::Char`] token, including its `#\` prefix
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidCharacter`] for unknown names, invalid
+/// scalar values and a `#\` with no character after it
+pub(crate) fn parse_char(token: &Token<'_>) -> Result<char, ParseError> {
+    let body = &token.text[2..];
+    let mut chars = body.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c);
+    }
+
+    let named = CHAR_NAMES.iter().find(|(name, _)| *name == body).map(|&(_, c)| c);
+    let scalar = || {
+        let hex = body.strip_prefix(['x', 'X'])?;
+        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+    };
+    named.or_else(scalar).ok_or_else(|| ParseError::InvalidCharacter {
+        span: token.span,
+        literal: token.text.to_string(),
+    })
+}
+
+/// Write a character in `#\` literal syntax.
+///
+/// Named characters use their name and other whitespace or control
+/// characters use the `#\x` hex form, so the output always reads back as
+/// the same character.
+pub(crate) fn write_char(f: &mut fmt::Formatter<'_>, c: char) -> fmt::Result {
+    if let Some((name, _)) = CHAR_NAMES.iter().find(|&&(_, named)| named == c) {
+        write!(f, "#\\{}", name)
+    } else if c.is_whitespace() || c.is_control() {
+        write!(f, "#\\x{:X}", c as u32)
+    } else {
+        write!(f, "#\\{}", c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(src: &str) -> Vec<&str> {
+        Lexer::new(src).map(|t| t.unwrap().text).collect()
+    }
+
+    #[test]
+    fn string_literal_test() {
+        assert_eq!(texts("(a \"b c\" d)"), vec!["(", "a", "\"b c\"", "d", ")"]);
+        assert_eq!(texts("\"(a)\""), vec!["\"(a)\""]);
+        assert_eq!(texts("\"say \\\"hi\\\"\" x"), vec!["\"say \\\"hi\\\"\"", "x"]);
+        assert!(matches!(
+            Lexer::new("(\"open").collect::<Result<Vec<_>, _>>(),
+            Err(ParseError::UnterminatedString { token, .. }) if token == "\"open"
+        ));
+    }
+
+    #[test]
+    fn comment_test() {
+        let src = "a ; note (x)\n#| outer #| inner |# |#b#;c";
+        let tokens: Vec<_> = Lexer::new(src).map(|t| t.unwrap()).map(|t| (t.kind, t.text)).collect();
+        assert_eq!(tokens, vec![
+            (TokenKind::Atom, "a"),
+            (TokenKind::LineComment, "; note (x)"),
+            (TokenKind::BlockComment, "#| outer #| inner |# |#"),
+            (TokenKind::Atom, "b#"),
+            (TokenKind::LineComment, ";c"),
+        ]);
+        assert_eq!(texts("#;(a b) c"), vec!["#;", "(", "a", "b", ")", "c"]);
+        assert_eq!(texts("`(a ,b ,@c)'d"), vec!["`", "(", "a", ",", "b", ",@", "c", ")", "'", "d"]);
+        assert!(matches!(
+            Lexer::new("#| a #| b |#").collect::<Result<Vec<_>, _>>(),
+            Err(ParseError::UnterminatedComment { token, .. }) if token == "#| a #| b |#"
+        ));
+    }
+
+    #[test]
+    fn bracket_test() {
+        let kinds: Vec<_> = Lexer::new("[a]{b c}#{d}#[e f]").brackets(true).map(|t| t.unwrap().kind).collect();
+        assert_eq!(kinds, vec![
+            TokenKind::LBracket, TokenKind::Atom, TokenKind::RBracket,
+            TokenKind::LBrace, TokenKind::Atom, TokenKind::Atom, TokenKind::RBrace,
+            TokenKind::HashLBrace, TokenKind::Atom, TokenKind::RBrace,
+            TokenKind::HashLBracket, TokenKind::Atom, TokenKind::Atom, TokenKind::RBracket,
+        ]);
+        let bracket_texts: Vec<_> = Lexer::new("a[0]").brackets(true).map(|t| t.unwrap().text).collect();
+        assert_eq!(bracket_texts, vec!["a", "[", "0", "]"]);
+        let texts: Vec<_> = Lexer::new("#{a} [b]").map(|t| t.unwrap().text).collect();
+        assert_eq!(texts, vec!["#{a}", "[b]"]);
+    }
+
+    #[test]
+    fn dialect_syntax_test() {
+        let lex = |lexer: Lexer<'static>| -> Vec<_> { lexer.map(|t| t.unwrap()).map(|t| (t.kind, t.text)).collect() };
+        assert_eq!(lex(Lexer::new("`(a, ~@b)").tilde_unquote(true)), vec![
+            (TokenKind::Quasiquote, "`"),
+            (TokenKind::LParen, "("),
+            (TokenKind::Atom, "a"),
+            (TokenKind::UnquoteSplicing, "~@"),
+            (TokenKind::Atom, "b"),
+            (TokenKind::RParen, ")"),
+        ]);
+        assert_eq!(lex(Lexer::new("#\\a").char_literals(false)), vec![(TokenKind::Atom, "#\\a")]);
+        assert_eq!(lex(Lexer::new("#u8(1)").bytevectors(true)), vec![
+            (TokenKind::HashU8LParen, "#u8("),
+            (TokenKind::Atom, "1"),
+            (TokenKind::RParen, ")"),
+        ]);
+        assert_eq!(lex(Lexer::new("#u8("))[0], (TokenKind::Atom, "#u8"));
+
+        let tokens = lex(Lexer::new("(3:a b12 x").verbatim_strings(true));
+        assert_eq!(tokens[1], (TokenKind::Verbatim, "3:a b"));
+        assert_eq!(tokens[2], (TokenKind::Atom, "12"));
+        assert_eq!(verbatim_contents(&Lexer::new("3:a b").verbatim_strings(true).next().unwrap().unwrap()), "a b");
+        assert!(matches!(
+            Lexer::new("(10:x)").verbatim_strings(true).collect::<Result<Vec<_>, _>>(),
+            Err(ParseError::InvalidVerbatim { .. })
+        ));
+    }
+
+    #[test]
+    fn char_literal_test() {
+        assert_eq!(texts("(#\\( #\\) #\\  #\\space)"), vec!["(", "#\\(", "#\\)", "#\\ ", "#\\space", ")"]);
+        assert_eq!(texts("#\\a\"b\""), vec!["#\\a", "\"b\""]);
+
+        let char_src = |src| parse_char(&Lexer::new(src).next().unwrap().unwrap());
+        assert_eq!(char_src("#\\a"), Ok('a'));
+        assert_eq!(char_src("#\\\u{3bb}"), Ok('\u{3bb}'));
+        assert_eq!(char_src("#\\x"), Ok('x'));
+        assert_eq!(char_src("#\\x41"), Ok('A'));
+        assert_eq!(char_src("#\\newline"), Ok('\n'));
+        assert_eq!(char_src("#\\delete"), Ok('\u{7F}'));
+        for invalid in ["#\\", "#\\bogus", "#\\xD800", "#\\xyz"] {
+            assert!(matches!(char_src(invalid), Err(ParseError::InvalidCharacter { .. })), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn span_test() {
+        let src = "(a\n  \"x\ny\" bc)";
+        let spans: Vec<_> = Lexer::new(src).map(|t| t.unwrap().span).collect();
+        assert_eq!(spans, vec![
+            Span { start: 0, end: 1, line: 1, column: 1 },
+            Span { start: 1, end: 2, line: 1, column: 2 },
+            Span { start: 5, end: 10, line: 2, column: 3 },
+            Span { start: 11, end: 13, line: 3, column: 4 },
+            Span { start: 13, end: 14, line: 3, column: 6 },
+        ]);
+    }
+
+    #[test]
+    fn unescape_test() {
+        let unescape_src = |src| unescape(&Lexer::new(src).next().unwrap().unwrap());
+        assert!(matches!(unescape_src("\"plain\""), Ok(Cow::Borrowed("plain"))));
+        assert_eq!(unescape_src("\"a\\nb\\t\\\"\\\\\"").unwrap(), "a\nb\t\"\\");
+        assert_eq!(unescape_src("\"\\u{41}\\u{1F600}\"").unwrap(), "A\u{1F600}");
+
+        let sequence = |src| match unescape_src(src) {
+            Err(ParseError::InvalidEscape { sequence, span }) => (sequence, span.start, span.column),
+            other => panic!("expected an invalid escape, got {:?}", other),
+        };
+        assert_eq!(sequence("\"ab\\q\""), ("\\q".to_string(), 3, 4));
+        assert_eq!(sequence("\"\\u{D800}\""), ("\\u{D800}".to_string(), 1, 2));
+        assert_eq!(sequence("\"\\u41\""), ("\\u".to_string(), 1, 2));
+        assert_eq!(sequence("\"\\u{zz} ok\""), ("\\u{zz}".to_string(), 1, 2));
+    }
+
+    #[test]
+    fn quoted_symbol_test() {
+        assert_eq!(texts("(|a b| c|d e|f |(|)"), vec!["(", "|a b|", "c|d e|f", "|(|", ")"]);
+        assert_eq!(texts("|a\\|b| x"), vec!["|a\\|b|", "x"]);
+        assert!(matches!(
+            Lexer::new("(a |b c)").collect::<Result<Vec<_>, _>>(),
+            Err(ParseError::UnterminatedSymbol { span, token }) if span.start == 3 && token == "|b c)"
+        ));
+
+        let unescape_src = |src| unescape_symbol(&Lexer::new(src).next().unwrap().unwrap());
+        assert!(matches!(unescape_src("|a b|"), Ok(Cow::Borrowed("a b"))));
+        assert_eq!(unescape_src("c|d e|f").unwrap(), "cd ef");
+        assert_eq!(unescape_src("|a\\|b\\\\\\n|").unwrap(), "a|b\\\n");
+        assert!(matches!(
+            unescape_src("x|\\q|"),
+            Err(ParseError::InvalidEscape { sequence, span }) if sequence == "\\q" && span.start == 2
+        ));
+    }
+}
@@ -8,10 +8,33 @@
 //! 
 //! - **Zero-copy parsing**: Uses borrowed string slices to avoid unnecessary allocations
 //! - **Fast-path optimizations**: Optimized number parsing and single-character symbols
-//! - **Production error handling**: Proper error types instead of panics
+//! - **Exact integers**: `i64` integers are kept distinct from `f64` floats
+//! - **Arbitrary precision** (`bignum` feature): Big integers and exact ratios like `1/3`
+//! - **Extended number syntax**: `#x1F`, `0b1010`, `1_000_000`, `+inf.0` and friends
+//! - **String literals**: Strings may contain whitespace, parentheses and escape sequences
+//! - **Character literals**: `#\a`, `#\space`, `#\newline` and `#\x41`
+//! - **Keywords**: `:key` (or `key:`) reads as a dedicated keyword variant
+//! - **Quoted symbols**: `|hello world|` symbols, so any name can be written and read back
+//! - **Collections**: `[a b]` vectors, `{k v}` maps and `#{a b}` sets
+//! - **Dotted pairs**: Improper lists like `(a . b)` for alists and cons data
+//! - **Dialects**: Presets for R7RS Scheme, Common Lisp, Clojure/EDN and Rivest S-expressions, plus runtime-configurable literal words and delimiters
+//! - **Rivest encodings**: Byte-exact canonical, advanced and base64 transport forms, with display hints read and written as `#[hint value]`
+//! - **Bytevectors**: `#u8(1 2 3)`, and octet strings that are not UTF-8
+//! - **Binary encoding**: Compact, versioned `to_bytes` with a zero-copy `from_bytes` for caching parsed trees
+//! - **Production error handling**: Proper error types with source positions instead of panics
+//! - **Source spans**: Optional byte ranges, lines and columns for every parsed node
+//! - **Comments**: Line, nested block and datum comments, optionally kept as trivia
+//! - **Reader macros**: `'`, `` ` ``, `,` and `,@` read as quoted nodes, or expand to `quote`, `quasiquote`, `unquote` and `unquote-splicing`
+//! - **Round-trip printing**: `Display` output always reads back as the same value
+//! - **Pretty printing**: Width-aware multi-line output with per-form indentation styles
+//! - **Serde support** (`serde` feature): `to_string` and `from_str` for any `Serialize`/`Deserialize` type, and serde impls for `OwnedExpression` itself
+//! - **JSON conversion** (`json` feature): `TryFrom`/`From` between `OwnedExpression` and `serde_json::Value`
+//! - **Formatter**: `format` and the `sexpfmt` binary reformat files, keeping comments and blank lines
 //! - **Memory efficient**: Pre-allocated vectors and optimized tokenization
 //! - **Compiler-friendly**: Designed for use in language compilers and interpreters
 //! - **Custom symbol types**: Trait-based system for custom symbol representations in owned expressions
+//! - **Symbol interning**: `InternedSymbol` ids compare in O(1), and `to_owned_with` takes a pluggable `SymbolInterner`
+//! - **Arena allocation** (`arena` feature): `read_in` builds an `ArenaExpression` tree in a bump `Arena`, freed all at once
 //! 
 //! # Quick Start
 //! 
@@ -34,12 +57,30 @@
 //! - [`OwnedExpression`]: Owned version with custom symbol support
 //! - [`OwnedSymbol`]: Trait for custom symbol types
 //! - [`StringOwnedSymbol`]: Default string-based symbol implementation
-//! - [`ParseError`]: Comprehensive error types
+//! - [`InternedSymbol`]: Interned symbol compared by id, backed by a [`SymbolTable`]
+//! - [`ParseError`]: Comprehensive error types with source positions and snippet rendering
+//! - [`Reader`]: Streaming iterator over the top-level forms of a source string
+//! - [`ReaderOptions`]: Builder for optional reader behaviour
+//! - [`Lexer`]: Character-level tokenizer yielding zero-copy [`Token`]s
+//! - [`Spanned`]: An expression paired with a [`SpanTree`] of source locations
+//! - [`PrettyOptions`]: Width and indentation rules for [`Expression::pretty`]
 //! 
 //! ## Main Functions
 //! 
 //! - [`read`]: Primary parsing function with error handling
+//! - [`read_all`]: Parse every top-level form of a file
+//! - [`read_strict`]: Parse exactly one form, rejecting trailing input
 //! - [`read_unchecked`]: Convenience function that panics on error
+//! - [`read_spanned_with`]: Spanned parsing with [`ReaderOptions`], e.g. to keep comments
+//! - [`read_with`]: Parsing with [`ReaderOptions`], e.g. a [`Dialect`] preset
+//! - [`read_spanned`]: Parsing with byte offsets, lines and columns for every node
+//! - [`format()`]: Reformat a source file, keeping comments and blank lines
+//! - [`Expression::to_bytes`] / [`Expression::from_bytes`]: Cache parsed
+//!   trees in a compact binary format, decoded without copying
+//! - [`read_canonical`] / [`read_advanced`]: Read Rivest canonical, advanced
+//!   and transport encodings, written back with [`Expression::to_canonical`]
+//! - `to_string` / `from_str`: Convert Rust values to and from S-expression
+//!   text with serde (`serde` feature)
 //! 
 //! # Performance
 //! 
@@ -108,7 +149,12 @@
 //! use sexpression::{read, ParseError};
 //! 
 //! let result = read("(unclosed");
-//! assert!(matches!(result, Err(ParseError::MissingClosingParen)));
+//! assert!(matches!(result, Err(ParseError::MissingClosingParen { .. })));
+//! 
+//! // Every error knows where it happened and can render a source snippet
+//! let err = result.unwrap_err();
+//! assert_eq!(err.span().column, 10);
+//! eprintln!("{}", err.render("(unclosed"));
 //! ```
 //! 
 //! ## Converting to Owned
@@ -116,12 +162,30 @@
 //! ```rust
 //! use sexpression::{Expression, OwnedExpression, StringOwnedSymbol, OwnedSymbol};
 //! 
-//! let borrowed = Expression::Symbol("hello");
+//! let borrowed = Expression::Symbol("hello".into());
 //! let owned: OwnedExpression<StringOwnedSymbol> = borrowed.to_owned();
 //! assert_eq!(owned, OwnedExpression::Symbol(StringOwnedSymbol::from_str("hello")));
 //! ```
 
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod binary;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod error;
+pub mod format;
+pub mod intern;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lexer;
+mod number;
+pub mod options;
+pub mod pretty;
 pub mod reader;
+pub mod rivest;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod span;
 
 // Re-export main types and functions for easy access
 pub use crate::reader::{
@@ -130,6 +194,81 @@ pub use crate::reader::{
     OwnedSymbol,
     StringOwnedSymbol,
     ParseError,
+    QuoteKind,
+    Reader,
     read,
+    read_all,
+    read_spanned,
+    read_spanned_with,
+    read_strict,
     read_unchecked,
+    read_with,
+};
+pub use crate::intern::{
+    InternedSymbol,
+    SymbolInterner,
+    SymbolTable,
+};
+pub use crate::options::{
+    Dialect,
+    KeywordStyle,
+    NumberSyntax,
+    QuoteStyle,
+    UnquoteStyle,
+    ReaderOptions,
+};
+pub use crate::format::{
+    format,
+    FormatOptions,
+};
+pub use crate::pretty::{
+    IndentStyle,
+    PrettyOptions,
+};
+pub use crate::rivest::{
+    read_advanced,
+    read_canonical,
+};
+pub use crate::error::{
+    DecodeError,
+    EncodeError,
+};
+pub use crate::span::{
+    Comment,
+    CommentKind,
+    Span,
+    SpanTree,
+    Spanned,
+};
+pub use crate::lexer::{
+    Lexer,
+    Token,
+    TokenKind,
+};
+#[cfg(feature = "arena")]
+pub use crate::arena::{
+    Arena,
+    ArenaExpression,
+    read_all_in,
+    read_in,
+    read_in_with,
+};
+#[cfg(feature = "serde")]
+pub use crate::de::{
+    from_expression,
+    from_str,
+};
+#[cfg(feature = "serde")]
+pub use crate::error::SerdeError;
+#[cfg(feature = "json")]
+pub use crate::json::{
+    JsonOptions,
+    ObjectStyle,
+    SymbolStyle,
+};
+#[cfg(feature = "serde")]
+pub use crate::ser::{
+    to_expression,
+    to_string,
+    to_string_pretty,
 };
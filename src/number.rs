@@ -0,0 +1,336 @@
+//! Number Literal Module
+//!
+//! This module classifies and parses numeric atoms for the reader. Decimal
+//! integers and floats are always recognized; radix prefixes (`#x1F`,
+//! `0x1F`), digit separators (`1_000_000`) and the special floats `+inf.0`,
+//! `-inf.0` and `+nan.0` are enabled through [`NumberSyntax`]. An atom with
+//! a radix prefix is only a number if everything after the prefix is digits
+//! of that radix, so names like `0xdeadbeef-handler` stay symbols.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read, Expression};
+//!
+//! assert_eq!(read("#xFF").unwrap(), Expression::Integer(255));
+//! assert_eq!(read("0b1010").unwrap(), Expression::Integer(10));
+//! assert_eq!(read("1_000_000").unwrap(), Expression::Integer(1_000_000));
+//! assert_eq!(read("-inf.0").unwrap(), Expression::Float(f64::NEG_INFINITY));
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+#[cfg(feature = "bignum")]
+use num_rational::BigRational;
+
+use crate::error::ParseError;
+use crate::lexer::Token;
+use crate::options::NumberSyntax;
+use crate::reader::Expression;
+
+/// Parse a numeric literal.
+///
+/// # Arguments
+///
+/// * `token` - The atom token to parse
+/// * `syntax` - The accepted literal forms
+///
+/// # Returns
+///
+/// `Ok(None)` if the token does not have number syntax
+///
+/// # Errors
+///
+/// Returns `ParseError::InvalidNumber` for ratios with a zero denominator, and
+/// `ParseError::IntegerOverflow` for integers beyond `i64` without the
+/// `bignum` feature
+pub(crate) fn parse_number<'a>(
+    token: &Token<'_>,
+    syntax: &NumberSyntax,
+) -> Result<Option<Expression<'a>>, ParseError> {
+    if syntax.special_floats {
+        match token.text {
+            "+inf.0" => return Ok(Some(Expression::Float(f64::INFINITY))),
+            "-inf.0" => return Ok(Some(Expression::Float(f64::NEG_INFINITY))),
+            "+nan.0" | "-nan.0" => return Ok(Some(Expression::Float(f64::NAN))),
+            _ => {}
+        }
+    }
+
+    let text = if syntax.digit_separators && token.text.contains('_') {
+        match remove_separators(token.text) {
+            Some(text) => Cow::Owned(text),
+            None => return Ok(None),
+        }
+    } else {
+        Cow::Borrowed(token.text)
+    };
+
+    if syntax.radix_prefixes {
+        if let Some((radix, rest)) = scheme_radix(&text) {
+            let (negative, digits) = split_sign(rest);
+            return match is_digits(digits, radix) {
+                true => parse_integer(token, negative, digits, radix).map(Some),
+                false => Ok(None),
+            };
+        }
+    }
+
+    let (negative, unsigned) = split_sign(&text);
+    if syntax.c_prefixes {
+        if let Some((radix, digits)) = c_radix(unsigned) {
+            return parse_integer(token, negative, digits, radix).map(Some);
+        }
+    }
+    if unsigned.is_empty() {
+        return Ok(None);
+    }
+
+    if unsigned.bytes().all(|b| b.is_ascii_digit()) {
+        return parse_integer(token, negative, unsigned, 10).map(Some);
+    }
+
+    #[cfg(feature = "bignum")]
+    if let Some(ratio) = parse_ratio(token, &text)? {
+        return Ok(Some(ratio));
+    }
+
+    if is_decimal_float(unsigned) {
+        return Ok(text.parse::<f64>().ok().map(Expression::Float));
+    }
+    Ok(None)
+}
+
+/// Write a float so that it reads back as the same float.
+///
+/// `Debug` formatting of `f64` always keeps a fractional part or exponent,
+/// so `1.0` prints as `1.0` instead of `1`. Infinities and NaN use the
+/// `+inf.0`, `-inf.0` and `+nan.0` spellings.
+pub(crate) fn write_float(f: &mut fmt::Formatter<'_>, n: f64) -> fmt::Result {
+    if n.is_nan() {
+        write!(f, "+nan.0")
+    } else if n.is_infinite() {
+        write!(f, "{}inf.0", if n > 0.0 { "+" } else { "-" })
+    } else {
+        write!(f, "{:?}", n)
+    }
+}
+
+/// Parse the digits of an integer literal in the given radix.
+///
+/// The sign has already been split off, so negative literals down to
+/// `i64::MIN` are handled by parsing the magnitude as a `u64`.
+fn parse_integer<'a>(
+    token: &Token<'_>,
+    negative: bool,
+    digits: &str,
+    radix: u32,
+) -> Result<Expression<'a>, ParseError> {
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(ParseError::InvalidNumber { span: token.span, literal: token.text.to_string() });
+    }
+
+    if let Ok(magnitude) = u64::from_str_radix(digits, radix) {
+        let value = if negative { -i128::from(magnitude) } else { i128::from(magnitude) };
+        if let Ok(n) = i64::try_from(value) {
+            return Ok(Expression::Integer(n));
+        }
+    }
+
+    #[cfg(feature = "bignum")]
+    if let Some(magnitude) = BigInt::parse_bytes(digits.as_bytes(), radix) {
+        return Ok(Expression::BigInt(if negative { -magnitude } else { magnitude }));
+    }
+    Err(ParseError::IntegerOverflow { span: token.span, literal: token.text.to_string() })
+}
+
+/// Parse an exact `[+|-]digits/digits` ratio literal.
+///
+/// Ratios are normalized, so a ratio that reduces to a whole number such as
+/// `4/2` reads as an integer.
+///
+/// # Returns
+///
+/// `Ok(None)` if the text does not have ratio syntax
+#[cfg(feature = "bignum")]
+fn parse_ratio<'a>(token: &Token<'_>, text: &str) -> Result<Option<Expression<'a>>, ParseError> {
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let Some((numer, denom)) = text.split_once('/') else {
+        return Ok(None);
+    };
+    if !is_digits(numer.strip_prefix(['+', '-']).unwrap_or(numer)) || !is_digits(denom) {
+        return Ok(None);
+    }
+    if denom.bytes().all(|b| b == b'0') {
+        return Err(ParseError::InvalidNumber { span: token.span, literal: token.text.to_string() });
+    }
+
+    let (Ok(numer), Ok(denom)) = (numer.parse::<BigInt>(), denom.parse::<BigInt>()) else {
+        return Ok(None);
+    };
+    let ratio = BigRational::new(numer, denom);
+    if !ratio.is_integer() {
+        return Ok(Some(Expression::Ratio(ratio)));
+    }
+    let n = ratio.to_integer();
+    Ok(Some(match i64::try_from(&n) {
+        Ok(n) => Expression::Integer(n),
+        Err(_) => Expression::BigInt(n),
+    }))
+}
+
+/// Check for `digits[.digits][(e|E)[+|-]digits]` with at least one mantissa
+/// digit and either a fractional part or an exponent.
+fn is_decimal_float(s: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    let int_len = digits(s);
+    let mut rest = &s[int_len..];
+    let mut mantissa_len = int_len;
+    let mut is_float = false;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let frac_len = digits(fraction);
+        mantissa_len += frac_len;
+        rest = &fraction[frac_len..];
+        is_float = true;
+    }
+    if mantissa_len == 0 {
+        return false;
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let exp_len = digits(exponent);
+        if exp_len == 0 {
+            return false;
+        }
+        rest = &exponent[exp_len..];
+        is_float = true;
+    }
+    is_float && rest.is_empty()
+}
+
+/// Split an optional leading sign, returning whether it was `-`.
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+/// Recognize a Scheme-style `#x`, `#o`, `#b` or `#d` radix prefix.
+fn scheme_radix(text: &str) -> Option<(u32, &str)> {
+    let rest = text.strip_prefix('#')?;
+    let radix = match rest.as_bytes().first()? {
+        b'x' | b'X' => 16,
+        b'o' | b'O' => 8,
+        b'b' | b'B' => 2,
+        b'd' | b'D' => 10,
+        _ => return None,
+    };
+    Some((radix, &rest[1..]))
+}
+
+/// Recognize a C-style `0x`, `0o` or `0b` radix prefix followed by digits.
+fn c_radix(unsigned: &str) -> Option<(u32, &str)> {
+    let rest = unsigned.strip_prefix('0')?;
+    let radix = match rest.as_bytes().first()? {
+        b'x' | b'X' => 16,
+        b'o' | b'O' => 8,
+        b'b' | b'B' => 2,
+        _ => return None,
+    };
+    let digits = &rest[1..];
+    is_digits(digits, radix).then_some((radix, digits))
+}
+
+/// Returns `true` if `digits` is non-empty and only has digits valid in
+/// `radix`.
+fn is_digits(digits: &str, radix: u32) -> bool {
+    !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix))
+}
+
+/// Remove `_` digit separators.
+///
+/// A separator must sit between two alphanumeric characters, so leading,
+/// trailing and doubled underscores are rejected.
+///
+/// # Returns
+///
+/// The literal without separators, or `None` if a separator is misplaced
+fn remove_separators(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'_' {
+            let before = i.checked_sub(1).map(|j| bytes[j]);
+            let after = bytes.get(i + 1).copied();
+            if !before.is_some_and(|c| c.is_ascii_alphanumeric()) || !after.is_some_and(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+        }
+    }
+    Some(text.replace('_', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{NumberSyntax, ReaderOptions};
+    use crate::reader::{read, Expression, Reader};
+
+    #[test]
+    fn radix_test() {
+        let read_number = |src| read(src).unwrap();
+        assert_eq!(read_number("#x1F"), Expression::Integer(31));
+        assert_eq!(read_number("#X-ff"), Expression::Integer(-255));
+        assert_eq!(read_number("#o17"), Expression::Integer(15));
+        assert_eq!(read_number("#b1010"), Expression::Integer(10));
+        assert_eq!(read_number("#d99"), Expression::Integer(99));
+        assert_eq!(read_number("0x1F"), Expression::Integer(31));
+        assert_eq!(read_number("-0x8000000000000000"), Expression::Integer(i64::MIN));
+        assert_eq!(read_number("0o777"), Expression::Integer(511));
+        assert_eq!(read_number("0b1111_0000"), Expression::Integer(240));
+        assert_eq!(read_number("#xDEAD_BEEF"), Expression::Integer(0xDEAD_BEEF));
+        for symbol in ["#define", "#x", "0x", "0xyz", "0b2", "0xfoo-bar", "0xdeadbeef-handler", "#d1.5", "#b102", "0x1G"] {
+            assert_eq!(read_number(symbol), Expression::Symbol(symbol.into()));
+        }
+        #[cfg(not(feature = "bignum"))]
+        assert!(matches!(read("0x1_0000_0000_0000_0000"), Err(crate::ParseError::IntegerOverflow { .. })));
+    }
+
+    #[test]
+    fn separator_and_special_float_test() {
+        let read_number = |src| read(src).unwrap();
+        assert_eq!(read_number("1_000_000"), Expression::Integer(1_000_000));
+        assert_eq!(read_number("-1_000.000_5"), Expression::Float(-1000.0005));
+        for symbol in ["1_", "1__0", "1_.5", "-_1"] {
+            assert_eq!(read_number(symbol), Expression::Symbol(symbol.into()));
+        }
+
+        assert_eq!(read_number("+inf.0"), Expression::Float(f64::INFINITY));
+        assert_eq!(read_number("-inf.0"), Expression::Float(f64::NEG_INFINITY));
+        assert!(matches!(read_number("+nan.0"), Expression::Float(n) if n.is_nan()));
+        assert_eq!(Expression::Float(f64::NEG_INFINITY).to_string(), "-inf.0");
+        assert_eq!(Expression::Float(f64::NAN).to_string(), "+nan.0");
+    }
+
+    #[test]
+    fn number_syntax_options_test() {
+        let options = ReaderOptions::new().number_syntax(NumberSyntax::decimal());
+        let read_decimal = |src| Reader::with_options(src, &options).next().unwrap().unwrap();
+        for symbol in ["#x1F", "0x1F", "1_000", "+inf.0"] {
+            assert_eq!(read_decimal(symbol), Expression::Symbol(symbol.into()));
+        }
+        assert_eq!(read_decimal("1.5e3"), Expression::Float(1500.0));
+
+        let options = ReaderOptions::new().number_syntax(NumberSyntax {
+            c_prefixes: false,
+            ..NumberSyntax::all()
+        });
+        let mut reader = Reader::with_options("#x1F 0x1F", &options);
+        assert_eq!(reader.next().unwrap().unwrap(), Expression::Integer(31));
+        assert_eq!(reader.next().unwrap().unwrap(), Expression::Symbol("0x1F".into()));
+    }
+}
@@ -0,0 +1,487 @@
+//! Reader Options Module
+//!
+//! This module provides [`ReaderOptions`], a builder for the optional
+//! behaviour of the reader. The defaults match [`read`](crate::read), so
+//! options only need to be set for the features a caller wants to change.
+//! A [`Dialect`] preset configures the literal syntax of a whole Lisp family
+//! at once.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{Reader, ReaderOptions};
+//!
+//! let src = "; the answer\n(define x 42)";
+//! let options = ReaderOptions::new().keep_comments(true);
+//! let form = Reader::with_options(src, &options).next_spanned().unwrap().unwrap();
+//! assert_eq!(form.spans.leading[0].span.source_text(src), "; the answer");
+//! ```
+
+use std::borrow::Cow;
+
+use crate::lexer::Lexer;
+
+/// A list of literal words: borrowed for the presets, owned when set at
+/// runtime.
+type Words = Cow<'static, [Cow<'static, str>]>;
+
+const NO_WORDS: &[Cow<'static, str>] = &[];
+const TRUE: &[Cow<'static, str>] = &[Cow::Borrowed("true")];
+const FALSE: &[Cow<'static, str>] = &[Cow::Borrowed("false")];
+const NULL: &[Cow<'static, str>] = &[Cow::Borrowed("null")];
+const NIL: &[Cow<'static, str>] = &[Cow::Borrowed("nil")];
+const SCHEME_TRUE: &[Cow<'static, str>] = &[Cow::Borrowed("#t"), Cow::Borrowed("#true")];
+const SCHEME_FALSE: &[Cow<'static, str>] = &[Cow::Borrowed("#f"), Cow::Borrowed("#false")];
+const LISP_TRUE: &[Cow<'static, str>] = &[Cow::Borrowed("t")];
+
+/// Configuration for the reader.
+///
+/// Options are set with builder methods and passed by reference to
+/// [`Reader::with_options`](crate::Reader::with_options) or
+/// [`read_spanned_with`](crate::read_spanned_with).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReaderOptions {
+    pub(crate) keep_comments: bool,
+    pub(crate) quote_style: QuoteStyle,
+    pub(crate) number_syntax: NumberSyntax,
+    pub(crate) keyword_style: KeywordStyle,
+    pub(crate) collections: bool,
+    pub(crate) dotted_pairs: bool,
+    pub(crate) true_literals: Words,
+    pub(crate) false_literals: Words,
+    pub(crate) null_literals: Words,
+    pub(crate) delimiters: Cow<'static, str>,
+    pub(crate) numbers: bool,
+    pub(crate) unquote_style: UnquoteStyle,
+    pub(crate) char_literals: bool,
+    pub(crate) bytevectors: bool,
+    pub(crate) verbatim_strings: bool,
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
+        ReaderOptions {
+            keep_comments: false,
+            quote_style: QuoteStyle::default(),
+            number_syntax: NumberSyntax::default(),
+            keyword_style: KeywordStyle::default(),
+            collections: true,
+            dotted_pairs: true,
+            true_literals: Cow::Borrowed(TRUE),
+            false_literals: Cow::Borrowed(FALSE),
+            null_literals: Cow::Borrowed(NULL),
+            delimiters: Cow::Borrowed(""),
+            numbers: true,
+            unquote_style: UnquoteStyle::default(),
+            char_literals: true,
+            bytevectors: true,
+            verbatim_strings: false,
+        }
+    }
+}
+
+/// A preset for the literal syntax of a Lisp family.
+///
+/// Selecting a dialect with [`ReaderOptions::dialect`] sets every syntax
+/// option at once; individual options can still be adjusted afterwards.
+/// Printing always uses this crate's own syntax, whatever the dialect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// This crate's JSON-like default: `true`, `false` and `null`, `:key`
+    /// keywords, `[]`/`{}`/`#{}` collections, dotted pairs, `#\a`
+    /// characters, `#u8(1 2)` bytevectors and every [`NumberSyntax`] form.
+    /// This is the syntax `Display` writes, so printed output reads back.
+    #[default]
+    Sexpression,
+    /// R7RS Scheme: `#t`/`#true` and `#f`/`#false`, no null (`'()` is just
+    /// the empty list), no keywords or collections, dotted pairs, `#\a`
+    /// characters, `#u8(1 2)` bytevectors, `#x1F` radix prefixes and
+    /// `+inf.0` special floats
+    Scheme,
+    /// Common Lisp: `t` is true and `nil` is null, `:key` keywords, no
+    /// collections, dotted pairs, `#\a` characters and `#x1F` radix prefixes
+    CommonLisp,
+    /// Clojure and EDN: `true`, `false` and `nil`, `:key` keywords,
+    /// `[]`/`{}`/`#{}` collections, `,` as whitespace with `~` and `~@` for
+    /// unquote, no dotted pairs or `#\` characters, and `0x1F` radix prefixes
+    Clojure,
+    /// Rivest S-expressions: every atom is a symbol or a string, including
+    /// `3:abc` verbatim strings, with no numbers, booleans, null, keywords,
+    /// collections, dotted pairs or characters. The binary canonical form
+    /// and the full advanced syntax are read by the [`rivest`](crate::rivest)
+    /// module.
+    Rivest,
+}
+
+/// Which characters mark unquote and unquote-splicing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UnquoteStyle {
+    /// Scheme and Common Lisp style `,x` and `,@x`
+    #[default]
+    Comma,
+    /// Clojure style `~x` and `~@x`, with `,` treated as whitespace
+    Tilde,
+}
+
+/// The numeric literal forms accepted in addition to plain decimals.
+///
+/// Decimal integers (`42`) and floats (`1.5`, `6.02e23`) are always
+/// recognized. Each flag enables one extra form; a literal in a disabled
+/// form reads as a symbol. The default enables everything.
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::NumberSyntax;
+///
+/// // Accept `#x1F` but treat `0x1F` as a symbol
+/// let syntax = NumberSyntax { c_prefixes: false, ..NumberSyntax::all() };
+/// # assert!(syntax.radix_prefixes);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NumberSyntax {
+    /// Scheme-style radix prefixes: `#x1F`, `#o17`, `#b1010` and `#d10`
+    pub radix_prefixes: bool,
+    /// C-style radix prefixes: `0x1F`, `0o17` and `0b1010`
+    pub c_prefixes: bool,
+    /// `_` separators between digits, as in `1_000_000`
+    pub digit_separators: bool,
+    /// The special floats `+inf.0`, `-inf.0`, `+nan.0` and `-nan.0`
+    pub special_floats: bool,
+}
+
+impl NumberSyntax {
+    /// Accept every supported literal form.
+    pub const fn all() -> Self {
+        NumberSyntax {
+            radix_prefixes: true,
+            c_prefixes: true,
+            digit_separators: true,
+            special_floats: true,
+        }
+    }
+
+    /// Accept plain decimal integers and floats only.
+    pub const fn decimal() -> Self {
+        NumberSyntax {
+            radix_prefixes: false,
+            c_prefixes: false,
+            digit_separators: false,
+            special_floats: false,
+        }
+    }
+}
+
+impl Default for NumberSyntax {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// How the `'`, `` ` ``, `,` and `,@` reader macros are represented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum QuoteStyle {
+    /// Expand to the standard list forms, e.g. `'x` reads as `(quote x)`
+    List,
+    /// Produce a dedicated [`Expression::Quoted`](crate::Expression::Quoted)
+    /// node, so that `'x` and `(quote x)` read back as they were printed
+    #[default]
+    Variant,
+}
+
+/// Where the colon of a keyword literal goes, if keywords are read at all.
+///
+/// A lone `:` is always a symbol. Keywords are printed in prefix form
+/// regardless of the style they were read with, which is why the default
+/// is [`Prefix`](KeywordStyle::Prefix): printed keywords read back. Before
+/// 0.3 the default read `:key` as a symbol; choose
+/// [`Symbol`](KeywordStyle::Symbol) for that.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KeywordStyle {
+    /// No keywords: `:key` and `key:` read as plain symbols
+    Symbol,
+    /// Clojure and Common Lisp style `:key`
+    #[default]
+    Prefix,
+    /// Suffix style `key:`, as used for keyword arguments in some Schemes
+    Suffix,
+}
+
+impl ReaderOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep comments as trivia on the span tree.
+    ///
+    /// Comments are always skipped when building expressions. With this
+    /// option enabled, spanned reads additionally record each comment in the
+    /// `leading` or `trailing` trivia of the nearest [`SpanTree`](crate::SpanTree)
+    /// node. Defaults to `false`.
+    pub fn keep_comments(mut self, keep: bool) -> Self {
+        self.keep_comments = keep;
+        self
+    }
+
+    /// Choose how quote reader macros are represented.
+    ///
+    /// Defaults to [`QuoteStyle::Variant`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{Reader, ReaderOptions, QuoteStyle, Expression};
+    ///
+    /// let options = ReaderOptions::new().quote_style(QuoteStyle::List);
+    /// let expr = Reader::with_options("'x", &options).next().unwrap().unwrap();
+    /// assert_eq!(expr, Expression::List(vec![Expression::Symbol("quote".into()), Expression::Symbol("x".into())]));
+    /// ```
+    pub fn quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = style;
+        self
+    }
+
+    /// Choose which numeric literal forms are accepted.
+    ///
+    /// Defaults to [`NumberSyntax::all`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{Reader, ReaderOptions, NumberSyntax, Expression};
+    ///
+    /// let options = ReaderOptions::new().number_syntax(NumberSyntax::decimal());
+    /// let expr = Reader::with_options("#x1F", &options).next().unwrap().unwrap();
+    /// assert_eq!(expr, Expression::Symbol("#x1F".into()));
+    /// ```
+    pub fn number_syntax(mut self, syntax: NumberSyntax) -> Self {
+        self.number_syntax = syntax;
+        self
+    }
+
+    /// Choose how keyword literals are recognized.
+    ///
+    /// Defaults to [`KeywordStyle::Prefix`]; [`KeywordStyle::Symbol`] turns
+    /// keywords off, reading `:key` as a plain symbol as 0.2 did.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{Reader, ReaderOptions, KeywordStyle, Expression};
+    ///
+    /// let options = ReaderOptions::new().keyword_style(KeywordStyle::Suffix);
+    /// let expr = Reader::with_options("name:", &options).next().unwrap().unwrap();
+    /// assert_eq!(expr, Expression::Keyword("name".into()));
+    /// ```
+    pub fn keyword_style(mut self, style: KeywordStyle) -> Self {
+        self.keyword_style = style;
+        self
+    }
+
+    /// Read `[...]` vectors, `{...}` maps and `#{...}` sets.
+    ///
+    /// When disabled, brackets and braces are ordinary symbol characters, as
+    /// they were by default before 0.3. Defaults to `true`, since `Display`
+    /// writes collections with them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{read_with, Expression, ReaderOptions};
+    ///
+    /// let options = ReaderOptions::new().collections(false);
+    /// assert_eq!(read_with("[a]", &options).unwrap(), Expression::Symbol("[a]".into()));
+    /// ```
+    pub fn collections(mut self, enabled: bool) -> Self {
+        self.collections = enabled;
+        self
+    }
+
+    /// Read `(a . b)` as a dotted list.
+    ///
+    /// When disabled, `.` inside a list is an ordinary symbol. Defaults to
+    /// `true`.
+    pub fn dotted_pairs(mut self, enabled: bool) -> Self {
+        self.dotted_pairs = enabled;
+        self
+    }
+
+    /// Choose the words that read as booleans.
+    ///
+    /// Defaults to `true` and `false`. The words may be string literals or
+    /// `String`s loaded at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `true_words` - Atoms that read as `Bool(true)`
+    /// * `false_words` - Atoms that read as `Bool(false)`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{Reader, ReaderOptions, Expression};
+    ///
+    /// let config = "no off";
+    /// let options = ReaderOptions::new().bool_literals(["yes", "on"], config.split(' ').map(String::from));
+    /// let expr = Reader::with_options("off", &options).next().unwrap().unwrap();
+    /// assert_eq!(expr, Expression::Bool(false));
+    /// ```
+    pub fn bool_literals<T, F>(mut self, true_words: T, false_words: F) -> Self
+    where
+        T: IntoIterator,
+        T::Item: Into<Cow<'static, str>>,
+        F: IntoIterator,
+        F::Item: Into<Cow<'static, str>>,
+    {
+        self.true_literals = true_words.into_iter().map(Into::into).collect();
+        self.false_literals = false_words.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Choose the words that read as `Null`.
+    ///
+    /// Defaults to `null`. Like [`bool_literals`](Self::bool_literals),
+    /// this takes string literals or `String`s.
+    pub fn null_literals<W>(mut self, words: W) -> Self
+    where
+        W: IntoIterator,
+        W::Item: Into<Cow<'static, str>>,
+    {
+        self.null_literals = words.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add characters that separate atoms.
+    ///
+    /// Each character of `chars` ends an atom and is otherwise skipped like
+    /// whitespace, as commas are in Clojure. Listed characters lose any
+    /// other meaning they had. Defaults to none.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{read_with, ReaderOptions};
+    ///
+    /// let options = ReaderOptions::new().delimiters("=&");
+    /// assert_eq!(read_with("(key=value&n=1)", &options).unwrap().to_string(), "(key value n 1)");
+    /// ```
+    pub fn delimiters(mut self, chars: impl Into<Cow<'static, str>>) -> Self {
+        self.delimiters = chars.into();
+        self
+    }
+
+    /// Set the literal words of a preset without allocating.
+    fn preset_literals(
+        mut self,
+        true_words: &'static [Cow<'static, str>],
+        false_words: &'static [Cow<'static, str>],
+        null_words: &'static [Cow<'static, str>],
+    ) -> Self {
+        self.true_literals = Cow::Borrowed(true_words);
+        self.false_literals = Cow::Borrowed(false_words);
+        self.null_literals = Cow::Borrowed(null_words);
+        self
+    }
+
+    /// Read numeric literals.
+    ///
+    /// When disabled, atoms such as `42` are symbols. Defaults to `true`.
+    pub fn numbers(mut self, enabled: bool) -> Self {
+        self.numbers = enabled;
+        self
+    }
+
+    /// Choose the characters that mark unquote.
+    ///
+    /// Defaults to [`UnquoteStyle::Comma`].
+    pub fn unquote_style(mut self, style: UnquoteStyle) -> Self {
+        self.unquote_style = style;
+        self
+    }
+
+    /// Read `#\a` character literals.
+    ///
+    /// When disabled, `#\a` is a symbol. Defaults to `true`.
+    pub fn char_literals(mut self, enabled: bool) -> Self {
+        self.char_literals = enabled;
+        self
+    }
+
+    /// Read R7RS `#u8(1 2 3)` bytevectors as [`Expression::Bytes`].
+    ///
+    /// When disabled, `#u8` is a symbol. Defaults to `true`.
+    ///
+    /// [`Expression::Bytes`]: crate::Expression::Bytes
+    pub fn bytevectors(mut self, enabled: bool) -> Self {
+        self.bytevectors = enabled;
+        self
+    }
+
+    /// Read Rivest-style `3:abc` verbatim strings as strings.
+    ///
+    /// Defaults to `false`.
+    pub fn verbatim_strings(mut self, enabled: bool) -> Self {
+        self.verbatim_strings = enabled;
+        self
+    }
+
+    /// A lexer for `src` that scans the syntax these options enable.
+    pub(crate) fn lexer<'a>(&self, src: &'a str) -> Lexer<'a> {
+        Lexer::new(src)
+            .brackets(self.collections)
+            .tilde_unquote(self.unquote_style == UnquoteStyle::Tilde)
+            .char_literals(self.char_literals)
+            .bytevectors(self.bytevectors)
+            .verbatim_strings(self.verbatim_strings)
+            .delimiters(self.delimiters.clone())
+    }
+
+    /// Apply the syntax preset of a [`Dialect`].
+    ///
+    /// This sets the literal words, number, keyword, collection, dotted
+    /// pair, unquote, character, bytevector and verbatim string options.
+    /// Comment and quote handling are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{read_with, Dialect, ReaderOptions, Expression};
+    ///
+    /// let options = ReaderOptions::new().dialect(Dialect::Scheme);
+    /// assert_eq!(read_with("#t", &options).unwrap(), Expression::Bool(true));
+    ///
+    /// let options = ReaderOptions::new().dialect(Dialect::CommonLisp);
+    /// assert_eq!(read_with("nil", &options).unwrap(), Expression::Null);
+    /// ```
+    pub fn dialect(self, dialect: Dialect) -> Self {
+        let default = ReaderOptions { keep_comments: self.keep_comments, quote_style: self.quote_style, ..Self::default() };
+        // The other families start without this crate's own extensions
+        let base = default.clone().keyword_style(KeywordStyle::Symbol).collections(false).bytevectors(false);
+        match dialect {
+            Dialect::Sexpression => default,
+            Dialect::Scheme => base
+                .preset_literals(SCHEME_TRUE, SCHEME_FALSE, NO_WORDS)
+                .bytevectors(true)
+                .number_syntax(NumberSyntax { c_prefixes: false, digit_separators: false, ..NumberSyntax::all() }),
+            Dialect::CommonLisp => base
+                .preset_literals(LISP_TRUE, NO_WORDS, NIL)
+                .keyword_style(KeywordStyle::Prefix)
+                .number_syntax(NumberSyntax { radix_prefixes: true, ..NumberSyntax::decimal() }),
+            Dialect::Clojure => base
+                .preset_literals(TRUE, FALSE, NIL)
+                .keyword_style(KeywordStyle::Prefix)
+                .collections(true)
+                .dotted_pairs(false)
+                .unquote_style(UnquoteStyle::Tilde)
+                .char_literals(false)
+                .number_syntax(NumberSyntax { c_prefixes: true, ..NumberSyntax::decimal() }),
+            Dialect::Rivest => base
+                .preset_literals(NO_WORDS, NO_WORDS, NO_WORDS)
+                .numbers(false)
+                .dotted_pairs(false)
+                .char_literals(false)
+                .verbatim_strings(true),
+        }
+    }
+}
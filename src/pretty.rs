@@ -0,0 +1,584 @@
+//! Pretty Printer Module
+//!
+//! This module lays out expressions over multiple lines. A form that fits in
+//! the remaining width is printed on one line exactly as `Display` would
+//! print it; a form that does not is broken according to an [`IndentStyle`],
+//! chosen per head symbol so that `define`, `let` and `lambda` get an
+//! indented body while ordinary calls line their arguments up.
+//!
+//! # Features
+//!
+//! - **Width-aware**: Breaks a form only when it does not fit, counting the
+//!   closing delimiters that follow it
+//! - **Indentation styles**: Aligned arguments, fixed indentation and
+//!   body indentation after a number of distinguished arguments
+//! - **Per-form rules**: Common definition and binding forms are preset and
+//!   any head symbol can be given its own style
+//! - **Stable output**: The layout depends only on the expression and the
+//!   options, never on how the source was formatted
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read, PrettyOptions};
+//!
+//! let expr = read("(define (f x) (let ((y (* x x))) (+ y 1)))").unwrap();
+//! let options = PrettyOptions::new().width(24);
+//! assert_eq!(expr.pretty(&options), "\
+//! (define (f x)
+//!   (let ((y (* x x)))
+//!     (+ y 1)))");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::reader::{Expression, OwnedExpression, OwnedSymbol, QuoteKind};
+
+/// How the arguments of a form are laid out when it does not fit on one line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IndentStyle {
+    /// Keep the first argument on the head's line and line the others up
+    /// under it:
+    ///
+    /// ```text
+    /// (foo a
+    ///      b)
+    /// ```
+    #[default]
+    Align,
+    /// Put every argument on its own line, indented from the open paren:
+    ///
+    /// ```text
+    /// (foo
+    ///   a
+    ///   b)
+    /// ```
+    Fixed,
+    /// Keep this many distinguished arguments on the head's line and indent
+    /// the remaining body from the open paren, as for `(define (f x) ...)`:
+    ///
+    /// ```text
+    /// (let ((x 1))
+    ///   body)
+    /// ```
+    Body(usize),
+}
+
+/// Configuration for the pretty printer.
+///
+/// Options are set with builder methods and passed by reference to
+/// [`Expression::pretty`] or [`OwnedExpression::pretty`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read, IndentStyle, PrettyOptions};
+///
+/// let options = PrettyOptions::new()
+///     .width(20)
+///     .form("my-let", IndentStyle::Body(1));
+/// let expr = read("(my-let (x 1) (print x) (print x))").unwrap();
+/// assert_eq!(expr.pretty(&options), "(my-let (x 1)\n  (print x)\n  (print x))");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    pub(crate) width: usize,
+    pub(crate) indent: usize,
+    pub(crate) style: IndentStyle,
+    pub(crate) forms: HashMap<String, IndentStyle>,
+}
+
+/// Forms whose body is indented, with their number of distinguished arguments.
+const BODY_FORMS: [(&str, usize); 21] = [
+    ("begin", 0),
+    ("case", 1),
+    ("defmacro", 2),
+    ("defn", 2),
+    ("define", 1),
+    ("define-syntax", 1),
+    ("defun", 2),
+    ("dolist", 1),
+    ("dotimes", 1),
+    ("fn", 1),
+    ("lambda", 1),
+    ("let", 1),
+    ("let*", 1),
+    ("let-values", 1),
+    ("letrec", 1),
+    ("letrec*", 1),
+    ("loop", 1),
+    ("progn", 0),
+    ("unless", 1),
+    ("when", 1),
+    ("with-open", 1),
+];
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            width: 80,
+            indent: 2,
+            style: IndentStyle::default(),
+            forms: BODY_FORMS
+                .iter()
+                .map(|&(name, distinguished)| (name.to_string(), IndentStyle::Body(distinguished)))
+                .collect(),
+        }
+    }
+}
+
+impl PrettyOptions {
+    /// Create the default options: 80 columns, an indent of 2, aligned
+    /// arguments and body indentation for common definition and binding
+    /// forms such as `define`, `defun`, `lambda` and `let`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the target line width. Defaults to 80.
+    ///
+    /// Atoms are never split, so a line holding a long atom can still
+    /// exceed the width.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the indentation used by [`IndentStyle::Fixed`] and
+    /// [`IndentStyle::Body`]. Defaults to 2.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Set the style for forms without a rule of their own. Defaults to
+    /// [`IndentStyle::Align`].
+    pub fn style(mut self, style: IndentStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the style for forms headed by the symbol `name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The head symbol, e.g. `define`
+    /// * `style` - The layout for its arguments
+    pub fn form(mut self, name: impl Into<String>, style: IndentStyle) -> Self {
+        self.forms.insert(name.into(), style);
+        self
+    }
+
+    /// Remove every per-form rule, including the presets, so all forms use
+    /// the default [`style`](Self::style).
+    pub fn clear_forms(mut self) -> Self {
+        self.forms.clear();
+        self
+    }
+
+    /// The style for a form headed by the symbol `head`.
+    fn style_for(&self, head: &str) -> IndentStyle {
+        self.forms.get(head).copied().unwrap_or(self.style)
+    }
+}
+
+impl<'a> Expression<'a> {
+    /// Print the expression over multiple lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The target width and indentation rules
+    ///
+    /// # Returns
+    ///
+    /// The laid out expression, without a trailing newline
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{read, PrettyOptions};
+    ///
+    /// let expr = read("(list alpha beta gamma)").unwrap();
+    /// assert_eq!(expr.pretty(&PrettyOptions::new()), "(list alpha beta gamma)");
+    /// assert_eq!(expr.pretty(&PrettyOptions::new().width(16)), "(list alpha\n      beta\n      gamma)");
+    /// ```
+    pub fn pretty(&self, options: &PrettyOptions) -> String {
+        Printer::new(options).finish(&Node::from_expression(self))
+    }
+}
+
+impl<S: OwnedSymbol> OwnedExpression<S> {
+    /// Print the expression over multiple lines.
+    ///
+    /// This lays out the expression exactly like [`Expression::pretty`].
+    pub fn pretty(&self, options: &PrettyOptions) -> String {
+        Printer::new(options).finish(&Node::from_owned(self))
+    }
+}
+
+/// A layout tree shared by borrowed and owned expressions and by the
+/// source formatter.
+///
+/// Every node knows the width it takes when printed on one line. Nodes that
+/// can never be printed on one line, such as lists containing a line
+/// comment, have a width of [`BROKEN`].
+#[derive(Debug)]
+pub(crate) enum Node {
+    /// A leaf, already rendered as text
+    Atom { text: String, symbol: bool },
+    /// A reader macro prefix such as `'`, or a block comment written before
+    /// an element
+    Prefix { prefix: Cow<'static, str>, inner: Box<Node>, width: usize },
+    /// A delimited sequence; `pairs` keeps map keys and values together
+    Seq { open: &'static str, close: &'static str, items: Vec<Node>, pairs: bool, width: usize },
+    /// A `;` comment, which ends its line. A `trailing` comment stays on the
+    /// line of the element before it.
+    Comment { text: String, trailing: bool },
+    /// A blank line kept between two elements
+    Blank,
+}
+
+/// The width of a node that must be broken over several lines.
+pub(crate) const BROKEN: usize = usize::MAX / 2;
+
+impl Node {
+    fn from_expression(expr: &Expression<'_>) -> Node {
+        let all = |items: &[Expression<'_>]| items.iter().map(Node::from_expression).collect();
+        match expr {
+            Expression::List(items) => Node::seq("(", all(items), ")", false),
+            Expression::DottedList(items, tail) if items.is_empty() => Node::from_expression(tail),
+            Expression::DottedList(items, tail) => {
+                let mut nodes: Vec<Node> = all(items);
+                // The dot stays with the tail when the list is broken
+                nodes.push(Node::prefix(". ", Node::from_expression(tail)));
+                Node::seq("(", nodes, ")", false)
+            }
+            Expression::Vector(items) => Node::seq("[", all(items), "]", false),
+            Expression::Set(items) => Node::seq("#{", all(items), "}", false),
+            Expression::Bytes(bytes) => Node::seq("#u8(", byte_atoms(bytes), ")", false),
+            Expression::Hinted(hint, value) => {
+                Node::seq("#[", vec![Node::from_expression(hint), Node::from_expression(value)], "]", false)
+            }
+            Expression::Map(entries) => {
+                let nodes = entries
+                    .iter()
+                    .flat_map(|(key, value)| [Node::from_expression(key), Node::from_expression(value)])
+                    .collect();
+                Node::seq("{", nodes, "}", true)
+            }
+            Expression::Quoted(kind, inner) => Node::quoted(*kind, Node::from_expression(inner)),
+            Expression::Symbol(_) => Node::atom(expr.to_string(), true),
+            _ => Node::atom(expr.to_string(), false),
+        }
+    }
+
+    fn from_owned<S: OwnedSymbol>(expr: &OwnedExpression<S>) -> Node {
+        let all = |items: &[OwnedExpression<S>]| items.iter().map(Node::from_owned).collect();
+        match expr {
+            OwnedExpression::List(items) => Node::seq("(", all(items), ")", false),
+            OwnedExpression::DottedList(items, tail) if items.is_empty() => Node::from_owned(tail),
+            OwnedExpression::DottedList(items, tail) => {
+                let mut nodes: Vec<Node> = all(items);
+                // The dot stays with the tail when the list is broken
+                nodes.push(Node::prefix(". ", Node::from_owned(tail)));
+                Node::seq("(", nodes, ")", false)
+            }
+            OwnedExpression::Vector(items) => Node::seq("[", all(items), "]", false),
+            OwnedExpression::Set(items) => Node::seq("#{", all(items), "}", false),
+            OwnedExpression::Bytes(bytes) => Node::seq("#u8(", byte_atoms(bytes), ")", false),
+            OwnedExpression::Hinted(hint, value) => {
+                Node::seq("#[", vec![Node::from_owned(hint), Node::from_owned(value)], "]", false)
+            }
+            OwnedExpression::Map(entries) => {
+                let nodes = entries
+                    .iter()
+                    .flat_map(|(key, value)| [Node::from_owned(key), Node::from_owned(value)])
+                    .collect();
+                Node::seq("{", nodes, "}", true)
+            }
+            OwnedExpression::Quoted(kind, inner) => Node::quoted(*kind, Node::from_owned(inner)),
+            OwnedExpression::Symbol(_) => Node::atom(expr.to_string(), true),
+            _ => Node::atom(expr.to_string(), false),
+        }
+    }
+
+    pub(crate) fn atom(text: String, symbol: bool) -> Node {
+        Node::Atom { text, symbol }
+    }
+
+    pub(crate) fn prefix(prefix: impl Into<Cow<'static, str>>, inner: Node) -> Node {
+        let prefix = prefix.into();
+        let prefix_width = if prefix.contains('\n') { BROKEN } else { prefix.chars().count() };
+        let width = prefix_width.saturating_add(inner.width()).min(BROKEN);
+        Node::Prefix { prefix, inner: Box::new(inner), width }
+    }
+
+    fn quoted(kind: QuoteKind, inner: Node) -> Node {
+        let first = match &inner {
+            Node::Atom { text, .. } => text.chars().next().unwrap_or(' '),
+            _ => ' ',
+        };
+        Node::prefix(kind.prefix_before(first), inner)
+    }
+
+    pub(crate) fn seq(open: &'static str, items: Vec<Node>, close: &'static str, pairs: bool) -> Node {
+        let spaces = items.len().saturating_sub(1);
+        let width = items
+            .iter()
+            .fold(open.len() + close.len() + spaces, |sum, item| sum.saturating_add(item.width()))
+            .min(BROKEN);
+        Node::Seq { open, close, items, pairs, width }
+    }
+
+    /// The width of the node printed on one line.
+    fn width(&self) -> usize {
+        match self {
+            // Multi-line atoms such as block comments cannot share a line
+            Node::Atom { text, .. } if text.contains('\n') => BROKEN,
+            Node::Atom { text, .. } => text.chars().count(),
+            Node::Prefix { width, .. } | Node::Seq { width, .. } => *width,
+            Node::Comment { .. } | Node::Blank => BROKEN,
+        }
+    }
+
+    /// Returns `true` for comments and blank lines.
+    fn is_trivia(&self) -> bool {
+        matches!(self, Node::Comment { .. } | Node::Blank)
+    }
+}
+
+/// The elements of a bytevector as atoms.
+fn byte_atoms(bytes: &[u8]) -> Vec<Node> {
+    bytes.iter().map(|b| Node::atom(b.to_string(), false)).collect()
+}
+
+/// Where an element of a broken sequence goes.
+#[derive(Debug, Clone, Copy)]
+struct Place {
+    /// Keep the element on the current line, after a space
+    same_line: bool,
+    /// The column to start a new line at, if the element goes on one
+    col: usize,
+}
+
+/// Writes a [`Node`] tree, tracking the current column.
+pub(crate) struct Printer<'o> {
+    options: &'o PrettyOptions,
+    out: String,
+    col: usize,
+}
+
+impl<'o> Printer<'o> {
+    pub(crate) fn new(options: &'o PrettyOptions) -> Self {
+        Printer { options, out: String::new(), col: 0 }
+    }
+
+    fn finish(mut self, node: &Node) -> String {
+        self.print(node, 0);
+        self.out
+    }
+
+    /// Print a sequence of top-level forms, one per line.
+    pub(crate) fn finish_top_level(mut self, items: &[Node]) -> String {
+        self.print_items(items, 0, |i| Place { same_line: i == 0, col: 0 });
+        // A final comment has no closing delimiter to put on the next line
+        if self.out.ends_with('\n') {
+            self.out.pop();
+        }
+        self.out
+    }
+
+    fn write(&mut self, s: &str) {
+        self.out.push_str(s);
+        match s.rfind('\n') {
+            Some(i) => self.col = s[i + 1..].chars().count(),
+            None => self.col += s.chars().count(),
+        }
+    }
+
+    fn newline(&mut self, col: usize) {
+        self.out.push('\n');
+        self.out.extend(std::iter::repeat_n(' ', col));
+        self.col = col;
+    }
+
+    /// Print `node` on one line.
+    fn flat(&mut self, node: &Node) {
+        match node {
+            Node::Atom { text, .. } | Node::Comment { text, .. } => self.write(text),
+            Node::Prefix { prefix, inner, .. } => {
+                self.write(prefix);
+                self.flat(inner);
+            }
+            Node::Seq { open, close, items, .. } => {
+                self.write(open);
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 { self.write(" "); }
+                    self.flat(item);
+                }
+                self.write(close);
+            }
+            Node::Blank => {}
+        }
+    }
+
+    /// Print `node`, breaking it over several lines if it does not fit.
+    ///
+    /// `trail` is the number of closing delimiters that will follow the node
+    /// on its last line.
+    fn print(&mut self, node: &Node, trail: usize) {
+        if self.col + node.width() + trail <= self.options.width {
+            return self.flat(node);
+        }
+        match node {
+            Node::Prefix { prefix, inner, .. } => {
+                self.write(prefix);
+                self.print(inner, trail);
+            }
+            Node::Seq { open, close, items, pairs, .. } => {
+                let start = self.col;
+                self.write(open);
+                let inner = self.col;
+                let trail = trail + close.len();
+                let head = items.iter().find(|item| !item.is_trivia());
+                match head {
+                    _ if *pairs => self.print_items(items, trail, |i| Place { same_line: i % 2 == 1 || i == 0, col: inner }),
+                    Some(Node::Atom { text, symbol: true }) if *open == "(" => {
+                        let body = start + self.options.indent;
+                        let aligned = inner + text.chars().count() + 1;
+                        match self.options.style_for(text) {
+                            IndentStyle::Align => self.print_items(items, trail, |i| Place {
+                                same_line: i <= 1,
+                                col: if i == 0 { inner } else { aligned },
+                            }),
+                            IndentStyle::Fixed => self.print_items(items, trail, |i| Place {
+                                same_line: i == 0,
+                                col: if i == 0 { inner } else { body },
+                            }),
+                            IndentStyle::Body(n) => self.print_items(items, trail, |i| Place {
+                                same_line: i <= n,
+                                col: if i == 0 { inner } else { body },
+                            }),
+                        }
+                    }
+                    _ => self.print_items(items, trail, |i| Place { same_line: i == 0, col: inner }),
+                }
+                self.write(close);
+            }
+            _ => self.flat(node),
+        }
+    }
+
+    /// Print the elements of a broken sequence.
+    ///
+    /// `place` gives the placement of the i-th element, not counting
+    /// comments and blank lines. A comment always ends its line, so the
+    /// element or closing delimiter after it starts a new one.
+    fn print_items(&mut self, items: &[Node], trail: usize, place: impl Fn(usize) -> Place) {
+        let last = items.iter().rposition(|item| !item.is_trivia());
+        let mut index = 0;
+        let mut line_start = true;
+        let mut must_break = false;
+        let mut blank = false;
+        for (i, item) in items.iter().enumerate() {
+            let at = place(index);
+            match item {
+                Node::Blank => blank = !line_start,
+                Node::Comment { text, trailing } => {
+                    if *trailing && !must_break {
+                        if !line_start { self.write(" "); }
+                    } else {
+                        self.break_line(at.col, &mut blank);
+                    }
+                    self.write(text);
+                    line_start = false;
+                    must_break = true;
+                }
+                _ => {
+                    if at.same_line && !must_break && !blank {
+                        if !line_start { self.write(" "); }
+                    } else if index > 0 || must_break {
+                        self.break_line(at.col, &mut blank);
+                    }
+                    self.print(item, if Some(i) == last { trail } else { 0 });
+                    index += 1;
+                    line_start = false;
+                    must_break = false;
+                }
+            }
+        }
+        if must_break {
+            self.newline(place(index.saturating_sub(1)).col);
+        }
+    }
+
+    /// Start a new line at `col`, after an empty line if one is pending.
+    fn break_line(&mut self, col: usize, blank: &mut bool) {
+        // Nothing to break at the very start of the output
+        if self.out.is_empty() {
+            *blank = false;
+            return;
+        }
+        if std::mem::take(blank) {
+            self.out.push('\n');
+        }
+        self.newline(col);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{read, StringOwnedSymbol};
+
+    fn pretty(src: &str, options: &PrettyOptions) -> String {
+        read(src).unwrap().pretty(options)
+    }
+
+    #[test]
+    fn layout_test() {
+        let options = PrettyOptions::new().width(30);
+        let src = "(define (fact n) (if (= n 0) 1 (* n (fact (- n 1)))))";
+        assert_eq!(pretty(src, &options), "\
+(define (fact n)
+  (if (= n 0)
+      1
+      (* n (fact (- n 1)))))");
+
+        let src = "(lambda (x) (display \"a long string here\") x)";
+        assert_eq!(pretty(src, &options), "\
+(lambda (x)
+  (display \"a long string here\")
+  x)");
+
+        let src = "{:name \"sexpression\" :deps [serde thiserror] :tags #{parser lisp}}";
+        assert_eq!(pretty(src, &options), "\
+{:name \"sexpression\"
+ :deps [serde thiserror]
+ :tags #{parser lisp}}");
+
+        assert_eq!(pretty("'(alpha beta gamma delta)", &PrettyOptions::new().width(16)), "\
+'(alpha beta
+        gamma
+        delta)");
+        assert_eq!(pretty("((f a) b c . d)", &PrettyOptions::new().width(8)), "((f a)\n b\n c\n . d)");
+        assert_eq!(pretty("(let x . (alpha beta))", &PrettyOptions::new().width(16)), "(let x\n  . (alpha beta))");
+    }
+
+    #[test]
+    fn style_test() {
+        let src = "(call alpha beta gamma)";
+        let narrow = PrettyOptions::new().width(12);
+        assert_eq!(pretty(src, &narrow.clone().style(IndentStyle::Fixed)), "(call\n  alpha\n  beta\n  gamma)");
+        assert_eq!(pretty(src, &narrow.clone().indent(4).form("call", IndentStyle::Body(1))), "(call alpha\n    beta\n    gamma)");
+        assert_eq!(pretty("(let (x) y z)", &narrow.clone().width(8).clear_forms()), "(let (x)\n     y\n     z)");
+
+        // Closing parens count towards the width
+        assert_eq!(pretty("(f (g a b))", &PrettyOptions::new().width(11)), "(f (g a b))");
+        assert_eq!(pretty("(f (g a b))", &PrettyOptions::new().width(10)), "(f (g a\n      b))");
+
+        let owned = read("(define x (list 1 2 3))").unwrap().to_owned::<StringOwnedSymbol>();
+        assert_eq!(owned.pretty(&narrow.width(15)), "(define x\n  (list 1 2 3))");
+    }
+}
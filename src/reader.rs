@@ -8,6 +8,11 @@
 //! 
 //! - **Zero-copy parsing**: Uses borrowed string slices to avoid unnecessary allocations
 //! - **Fast-path optimizations**: Optimized number parsing and single-character symbols
+//! - **String literals**: Strings may contain whitespace, parentheses and escape sequences
+//! - **Character literals**: `#\a`, `#\space` and `#\x41` read as [`Expression::Char`]
+//! - **Bytevectors**: `#u8(1 2 255)` reads as [`Expression::Bytes`]
+//! - **Round-trip printing**: `Display` escapes strings and writes symbols that
+//!   need it as `|...|`, so printed output reads back as the same value
 //! - **Production error handling**: Proper error types instead of panics
 //! - **Memory efficient**: Pre-allocated vectors and optimized tokenization
 //! - **Custom symbol types**: Trait-based system for custom symbol representations in owned expressions
@@ -24,7 +29,25 @@
 //! }
 //! ```
 
+use std::borrow::Cow;
 use std::fmt;
+use std::marker::PhantomData;
+use std::vec::Drain;
+
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+#[cfg(feature = "bignum")]
+use num_rational::BigRational;
+
+use crate::number::{parse_number, write_float};
+
+pub use crate::error::ParseError;
+use crate::intern::SymbolInterner;
+use crate::lexer::{
+    parse_char, unescape, unescape_symbol, verbatim_contents, write_char, write_quoted, Lexer, Token, TokenKind,
+};
+use crate::options::{KeywordStyle, QuoteStyle, ReaderOptions};
+use crate::span::{Comment, CommentKind, Span, SpanTree, Spanned};
 
 /// Trait for custom symbol types in owned S-expressions.
 /// 
@@ -99,13 +122,66 @@ pub trait OwnedSymbol: Sized + fmt::Debug + Clone + PartialEq {
     /// 
     /// A `fmt::Result` indicating success or failure
     fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /// The name [`from_str`](OwnedSymbol::from_str) turns back into this
+    /// symbol.
+    /// 
+    /// Encodings that store names, such as
+    /// [`OwnedExpression::to_bytes`], read it through this method. The
+    /// default reads the output of [`display`](OwnedSymbol::display) back,
+    /// so a symbol displayed as `|a b|` is named `a b`. Override it if the
+    /// name is at hand without formatting.
+    fn symbol_name(&self) -> Cow<'_, str> {
+        let text = DisplaySymbol(self).to_string();
+        Cow::Owned(read_symbol_name(&text).into_owned())
+    }
+
+    /// Serialize the symbol when an [`OwnedExpression`] is serialized.
+    /// 
+    /// The default writes the output of [`display`](OwnedSymbol::display) as
+    /// a string, which the default
+    /// [`deserialize_symbol`](OwnedSymbol::deserialize_symbol) reads back.
+    /// Override both if that output does not turn back into the same symbol
+    /// through [`from_str`](OwnedSymbol::from_str).
+    /// 
+    /// Only available with the `serde` feature.
+    #[cfg(feature = "serde")]
+    fn serialize_symbol<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_str(&DisplaySymbol(self))
+    }
+
+    /// Deserialize a symbol written by
+    /// [`serialize_symbol`](OwnedSymbol::serialize_symbol).
+    /// 
+    /// The default reads a string and passes it to
+    /// [`from_str`](OwnedSymbol::from_str). A name that `display` quoted as
+    /// `|a b|`, as the crate's own symbols do, is read back without its bars
+    /// first.
+    /// 
+    /// Only available with the `serde` feature.
+    #[cfg(feature = "serde")]
+    fn deserialize_symbol<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(Self::from_str(&read_symbol_name(&text)))
+    }
+}
+
+/// Formats a symbol with [`OwnedSymbol::display`].
+struct DisplaySymbol<'a, S>(&'a S);
+
+impl<S: OwnedSymbol> fmt::Display for DisplaySymbol<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.display(f)
+    }
 }
 
 /// Default string-based symbol implementation for owned expressions.
 /// 
 /// This provides the standard string-based symbol representation
-/// used by the default owned expression parser.
+/// used by the default owned expression parser. With the `serde` feature it
+/// serializes as its name.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct StringOwnedSymbol(String);
 
 impl OwnedSymbol for StringOwnedSymbol {
@@ -114,7 +190,27 @@ impl OwnedSymbol for StringOwnedSymbol {
     }
     
     fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write_symbol(f, &self.0)
+    }
+
+    fn symbol_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.0)
+    }
+
+    #[cfg(feature = "serde")]
+    fn serialize_symbol<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serde::Serialize::serialize(self, serializer)
+    }
+
+    #[cfg(feature = "serde")]
+    fn deserialize_symbol<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+}
+
+impl AsRef<str> for StringOwnedSymbol {
+    fn as_ref(&self) -> &str {
+        &self.0
     }
 }
 
@@ -137,54 +233,248 @@ impl fmt::Display for StringOwnedSymbol {
 /// use sexpression::Expression;
 /// 
 /// // Numbers
-/// let num = Expression::Number(42.0);
+/// let int = Expression::Integer(42);
+/// let float = Expression::Float(2.5);
 /// 
 /// // Symbols
-/// let sym = Expression::Symbol("define");
+/// let sym = Expression::Symbol("define".into());
 /// 
 /// // Lists
 /// let list = Expression::List(vec![
-///     Expression::Symbol("+"),
-///     Expression::Number(1.0),
-///     Expression::Number(2.0)
+///     Expression::Symbol("+".into()),
+///     Expression::Integer(1),
+///     Expression::Integer(2)
 /// ]);
 /// ```
 #[derive(PartialEq, Debug)]
 pub enum Expression<'a> {
-    /// A numeric literal (f64)
-    Number(f64),
+    /// An exact integer literal such as `42` or `-7`
+    Integer(i64),
+    /// A floating point literal such as `1.0` or `6.02e23`
+    Float(f64),
+    /// An integer literal outside the range of `i64`
+    #[cfg(feature = "bignum")]
+    BigInt(BigInt),
+    /// An exact, non-integral rational literal such as `1/3`
+    #[cfg(feature = "bignum")]
+    Ratio(BigRational),
     /// A boolean literal
     Bool(bool),
-    /// A string literal (borrowed from source)
-    Str(&'a str),
-    /// A symbol/identifier (borrowed from source)
-    Symbol(&'a str),
+    /// A string literal (borrowed from source unless it contained escapes)
+    Str(Cow<'a, str>),
+    /// A character literal such as `#\a` or `#\space`
+    Char(char),
+    /// A symbol/identifier (borrowed from source unless written with escapes
+    /// inside `|...|`)
+    Symbol(Cow<'a, str>),
+    /// A keyword such as `:key`, stored without its colon (see [`KeywordStyle`])
+    Keyword(Cow<'a, str>),
     /// A list of expressions
     List(Vec<Expression<'a>>),
+    /// An improper list such as `(a . b)` or `(a b . c)`: the elements before
+    /// the dot (at least one when read) and the tail after it. A tail that is
+    /// itself a list, as in `(a . (b))`, is kept as written. With no elements
+    /// before the dot, the value is just its tail and prints as it.
+    DottedList(Vec<Expression<'a>>, Box<Expression<'a>>),
+    /// A vector literal such as `[a b]`
+    Vector(Vec<Expression<'a>>),
+    /// A map literal such as `{k v}`, as key-value pairs in source order
+    Map(Vec<(Expression<'a>, Expression<'a>)>),
+    /// A set literal such as `#{a b}`, with elements in source order
+    Set(Vec<Expression<'a>>),
+    /// A bytevector such as `#u8(1 2 255)`, or an octet string that is not
+    /// UTF-8 (borrowed when read from binary or canonical input)
+    Bytes(Cow<'a, [u8]>),
+    /// A value with a display hint such as `#[text/plain hello]`: the hint,
+    /// then the value it describes (see [`crate::rivest`])
+    Hinted(Box<Expression<'a>>, Box<Expression<'a>>),
+    /// A quoted expression such as `'x` (only with [`QuoteStyle::Variant`])
+    Quoted(QuoteKind, Box<Expression<'a>>),
     /// A null value
     Null,
 }
 
+/// The reader macros that wrap the expression following them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuoteKind {
+    /// `'x`, equivalent to `(quote x)`
+    Quote,
+    /// `` `x ``, equivalent to `(quasiquote x)`
+    Quasiquote,
+    /// `,x`, equivalent to `(unquote x)`
+    Unquote,
+    /// `,@x`, equivalent to `(unquote-splicing x)`
+    UnquoteSplicing,
+}
+
+impl QuoteKind {
+    /// The symbol naming the list form, e.g. `quote`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            QuoteKind::Quote => "quote",
+            QuoteKind::Quasiquote => "quasiquote",
+            QuoteKind::Unquote => "unquote",
+            QuoteKind::UnquoteSplicing => "unquote-splicing",
+        }
+    }
+
+    /// The reader macro prefix, e.g. `'`.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            QuoteKind::Quote => "'",
+            QuoteKind::Quasiquote => "`",
+            QuoteKind::Unquote => ",",
+            QuoteKind::UnquoteSplicing => ",@",
+        }
+    }
+
+    /// The prefix to write before an expression whose text starts with
+    /// `first`.
+    ///
+    /// `,@x` reads as unquote-splicing, so an unquote is separated by a space
+    /// from an expression starting with `@`, as in `, @x`.
+    pub(crate) fn prefix_before(self, first: char) -> &'static str {
+        match (self, first) {
+            (QuoteKind::Unquote, '@') => ", ",
+            _ => self.prefix(),
+        }
+    }
+}
+
 impl<'a> fmt::Display for Expression<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expression::Number(n) => write!(f, "{}", n),
+            Expression::Integer(n) => write!(f, "{}", n),
+            Expression::Float(n) => write_float(f, *n),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => write!(f, "{}", n),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(r) => write!(f, "{}", r),
             Expression::Bool(b) => write!(f, "{}", b),
-            Expression::Str(s) => write!(f, "\"{}\"", s),
-            Expression::Symbol(s) => write!(f, "{}", s),
-            Expression::List(list) => {
-                write!(f, "(")?;
-                for (i, expr) in list.iter().enumerate() {
-                    if i > 0 { write!(f, " ")?; }
-                    write!(f, "{}", expr)?;
-                }
-                write!(f, ")")
+            Expression::Str(s) => write_quoted(f, s, '"'),
+            Expression::Char(c) => write_char(f, *c),
+            Expression::Symbol(s) => write_symbol(f, s),
+            Expression::Keyword(k) => {
+                write!(f, ":")?;
+                write_symbol(f, k)
             }
+            Expression::List(list) => write_delimited(f, "(", list, ")"),
+            // With no elements before the dot, the list is just its tail
+            Expression::DottedList(list, tail) if list.is_empty() => write!(f, "{}", tail),
+            Expression::DottedList(list, tail) => {
+                write_delimited(f, "(", list, "")?;
+                write!(f, " . {})", tail)
+            }
+            Expression::Vector(items) => write_delimited(f, "[", items, "]"),
+            Expression::Map(entries) => write_map(f, entries),
+            Expression::Set(items) => write_delimited(f, "#{", items, "}"),
+            Expression::Bytes(bytes) => write_delimited(f, "#u8(", bytes, ")"),
+            Expression::Hinted(hint, value) => write!(f, "#[{} {}]", hint, value),
+            Expression::Quoted(kind, expr) => write_quote(f, *kind, expr),
             Expression::Null => write!(f, "null"),
         }
     }
 }
 
+/// Write `items` separated by spaces between `open` and `close`.
+pub(crate) fn write_delimited<T: fmt::Display>(f: &mut fmt::Formatter<'_>, open: &str, items: &[T], close: &str) -> fmt::Result {
+    write!(f, "{}", open)?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 { write!(f, " ")?; }
+        write!(f, "{}", item)?;
+    }
+    write!(f, "{}", close)
+}
+
+/// Write a symbol name so that it reads back as the same symbol.
+/// 
+/// Names that would read as something else, such as `42`, `true`, `.`,
+/// `:key`, `a b` or the empty name, are written between bars as `|a b|`.
+pub(crate) fn write_symbol(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    if is_plain_symbol(name) {
+        f.write_str(name)
+    } else {
+        write_quoted(f, name, '|')
+    }
+}
+
+/// Returns `true` if `name` on its own reads as the symbol `name` with the
+/// default [`ReaderOptions`].
+fn is_plain_symbol(name: &str) -> bool {
+    // Fast path: an identifier like `list->vector` cannot read as a number,
+    // keyword or other atom, so only the literal words need checking
+    let bytes = name.as_bytes();
+    if bytes.first().is_some_and(u8::is_ascii_alphabetic)
+        && bytes.iter().all(|&b| b.is_ascii_alphanumeric() || b"-_*+!?<>=/".contains(&b))
+    {
+        return !matches!(name, "true" | "false" | "null");
+    }
+    lexes_as_symbol(name)
+}
+
+/// Returns `true` if the lexer and atom parser read `name` as the symbol
+/// `name` with the default [`ReaderOptions`].
+fn lexes_as_symbol(name: &str) -> bool {
+    if name == "." || name.contains('|') {
+        return false;
+    }
+    let options = ReaderOptions::new();
+    match options.lexer(name).next_token() {
+        Ok(Some(token)) if token.kind == TokenKind::Atom && token.text.len() == name.len() => {
+            matches!(parse_atom(&token, &options), Ok(Expression::Symbol(_)))
+        }
+        _ => false,
+    }
+}
+
+/// Read back a symbol name written by [`write_symbol`].
+///
+/// Text that is not a single atom with `|...|` sections is returned as is.
+fn read_symbol_name(text: &str) -> Cow<'_, str> {
+    if !text.contains('|') {
+        return Cow::Borrowed(text);
+    }
+    match ReaderOptions::new().lexer(text).next_token() {
+        Ok(Some(token)) if token.kind == TokenKind::Atom && token.text.len() == text.len() => {
+            unescape_symbol(&token).unwrap_or(Cow::Borrowed(text))
+        }
+        _ => Cow::Borrowed(text),
+    }
+}
+
+/// Write a quoted expression as its reader macro prefix and the expression.
+pub(crate) fn write_quote<T: fmt::Display>(f: &mut fmt::Formatter<'_>, kind: QuoteKind, expr: &T) -> fmt::Result {
+    fmt::Write::write_fmt(&mut PrefixWriter { f, prefix: Some(kind) }, format_args!("{}", expr))
+}
+
+/// Writes the prefix of a quote just before the first text of the quoted
+/// expression, once it is known whether that text starts with `@`.
+struct PrefixWriter<'f, 'g> {
+    f: &'f mut fmt::Formatter<'g>,
+    prefix: Option<QuoteKind>,
+}
+
+impl fmt::Write for PrefixWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let (Some(kind), Some(first)) = (self.prefix, s.chars().next()) {
+            self.f.write_str(kind.prefix_before(first))?;
+            self.prefix = None;
+        }
+        self.f.write_str(s)
+    }
+}
+
+/// Write map entries as `{k1 v1 k2 v2}`.
+pub(crate) fn write_map<T: fmt::Display>(f: &mut fmt::Formatter<'_>, entries: &[(T, T)]) -> fmt::Result {
+    write!(f, "{{")?;
+    for (i, (key, value)) in entries.iter().enumerate() {
+        if i > 0 { write!(f, " ")?; }
+        write!(f, "{} {}", key, value)?;
+    }
+    write!(f, "}}")
+}
+
 /// Owned version of Expression with custom symbol support.
 /// 
 /// This is useful when you need to store expressions independently of the
@@ -200,21 +490,73 @@ impl<'a> fmt::Display for Expression<'a> {
 /// let expr = OwnedExpression::<StringOwnedSymbol>::Symbol(StringOwnedSymbol::from_str("hello"));
 /// 
 /// // Convert from borrowed expression
-/// let borrowed = Expression::Symbol("hello");
+/// let borrowed = Expression::Symbol("hello".into());
 /// let owned: OwnedExpression<StringOwnedSymbol> = borrowed.to_owned();
 /// ```
+///
+/// With the `serde` feature, owned expressions implement `Serialize` and
+/// `Deserialize` as an externally tagged enum, e.g. `{"Integer": 1}` in
+/// JSON, so parsed trees can be stored in any serde format. Symbols go
+/// through [`OwnedSymbol::serialize_symbol`] and
+/// [`OwnedSymbol::deserialize_symbol`]. Note that this is a lossless encoding
+/// of the tree, not the S-expression text that
+/// [`to_string`](crate::to_string) would produce for an ordinary value.
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "S: OwnedSymbol")
+)]
 pub enum OwnedExpression<S: OwnedSymbol = StringOwnedSymbol> {
-    /// A numeric literal (f64)
-    Number(f64),
+    /// An exact integer literal
+    Integer(i64),
+    /// A floating point literal
+    Float(f64),
+    /// An integer literal outside the range of `i64`
+    #[cfg(feature = "bignum")]
+    BigInt(BigInt),
+    /// An exact, non-integral rational literal
+    #[cfg(feature = "bignum")]
+    Ratio(BigRational),
     /// A boolean literal
     Bool(bool),
     /// A string literal (owned)
     Str(String),
+    /// A character literal
+    Char(char),
     /// A symbol/identifier (custom type)
-    Symbol(S),
+    Symbol(
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "OwnedSymbol::serialize_symbol", deserialize_with = "OwnedSymbol::deserialize_symbol")
+        )]
+        S,
+    ),
+    /// A keyword, stored without its colon (same type as symbols)
+    Keyword(
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "OwnedSymbol::serialize_symbol", deserialize_with = "OwnedSymbol::deserialize_symbol")
+        )]
+        S,
+    ),
     /// A list of expressions
     List(Vec<OwnedExpression<S>>),
+    /// An improper list: the elements before the dot and the tail after it.
+    /// With no elements before the dot it prints as its tail.
+    DottedList(Vec<OwnedExpression<S>>, Box<OwnedExpression<S>>),
+    /// A vector literal
+    Vector(Vec<OwnedExpression<S>>),
+    /// A map literal, as key-value pairs
+    Map(Vec<(OwnedExpression<S>, OwnedExpression<S>)>),
+    /// A set literal
+    Set(Vec<OwnedExpression<S>>),
+    /// A bytevector or non-UTF-8 octet string
+    Bytes(Vec<u8>),
+    /// A value with a display hint: the hint, then the value
+    Hinted(Box<OwnedExpression<S>>, Box<OwnedExpression<S>>),
+    /// A quoted expression such as `'x`
+    Quoted(QuoteKind, Box<OwnedExpression<S>>),
     /// A null value
     Null,
 }
@@ -222,40 +564,38 @@ pub enum OwnedExpression<S: OwnedSymbol = StringOwnedSymbol> {
 impl<S: OwnedSymbol> fmt::Display for OwnedExpression<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            OwnedExpression::Number(n) => write!(f, "{}", n),
+            OwnedExpression::Integer(n) => write!(f, "{}", n),
+            OwnedExpression::Float(n) => write_float(f, *n),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::BigInt(n) => write!(f, "{}", n),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::Ratio(r) => write!(f, "{}", r),
             OwnedExpression::Bool(b) => write!(f, "{}", b),
-            OwnedExpression::Str(s) => write!(f, "\"{}\"", s),
+            OwnedExpression::Str(s) => write_quoted(f, s, '"'),
+            OwnedExpression::Char(c) => write_char(f, *c),
             OwnedExpression::Symbol(sym) => sym.display(f),
-            OwnedExpression::List(list) => {
-                write!(f, "(")?;
-                for (i, expr) in list.iter().enumerate() {
-                    if i > 0 { write!(f, " ")?; }
-                    write!(f, "{}", expr)?;
-                }
-                write!(f, ")")
+            OwnedExpression::Keyword(sym) => {
+                write!(f, ":")?;
+                sym.display(f)
+            }
+            OwnedExpression::List(list) => write_delimited(f, "(", list, ")"),
+            // With no elements before the dot, the list is just its tail
+            OwnedExpression::DottedList(list, tail) if list.is_empty() => write!(f, "{}", tail),
+            OwnedExpression::DottedList(list, tail) => {
+                write_delimited(f, "(", list, "")?;
+                write!(f, " . {})", tail)
             }
+            OwnedExpression::Vector(items) => write_delimited(f, "[", items, "]"),
+            OwnedExpression::Map(entries) => write_map(f, entries),
+            OwnedExpression::Set(items) => write_delimited(f, "#{", items, "}"),
+            OwnedExpression::Bytes(bytes) => write_delimited(f, "#u8(", bytes, ")"),
+            OwnedExpression::Hinted(hint, value) => write!(f, "#[{} {}]", hint, value),
+            OwnedExpression::Quoted(kind, expr) => write_quote(f, *kind, expr),
             OwnedExpression::Null => write!(f, "null"),
         }
     }
 }
 
-/// Parse errors that can occur during S-expression parsing.
-/// 
-/// This enum provides detailed error information for debugging and
-/// error handling in production environments.
-#[derive(Debug, thiserror::Error)]
-pub enum ParseError {
-    /// Unexpected end of input while parsing
-    #[error("Unexpected EOF")]
-    UnexpectedEOF,
-    /// Missing closing parenthesis in a list
-    #[error("Missing closing parenthesis")]
-    MissingClosingParen,
-    /// Unexpected closing parenthesis (no matching opening parenthesis)
-    #[error("Unexpected closing parenthesis")]
-    UnexpectedClosingParen,
-}
-
 impl<'a> Expression<'a> {
     /// Convert a borrowed expression to an owned expression.
     /// 
@@ -267,167 +607,782 @@ impl<'a> Expression<'a> {
     /// ```rust
 /// use sexpression::{Expression, StringOwnedSymbol, OwnedSymbol, OwnedExpression};
 /// 
-/// let borrowed = Expression::Symbol("hello");
+/// let borrowed = Expression::Symbol("hello".into());
 /// let owned: OwnedExpression<StringOwnedSymbol> = borrowed.to_owned();
 /// assert_eq!(owned, OwnedExpression::Symbol(StringOwnedSymbol::from_str("hello")));
 /// ```
     pub fn to_owned<S: OwnedSymbol>(&self) -> OwnedExpression<S> {
+        self.to_owned_with(&mut FromStrInterner(PhantomData))
+    }
+
+    /// Convert a borrowed expression to an owned expression, creating every
+    /// symbol and keyword through `interner`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interner` - The [`SymbolInterner`] that builds the owned symbols
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use sexpression::{read, SymbolTable};
+    /// 
+    /// let expr = read("(f x x)").unwrap();
+    /// let owned = SymbolTable::with(|table| expr.to_owned_with(table));
+    /// assert_eq!(owned.to_string(), "(f x x)");
+    /// ```
+    pub fn to_owned_with<I: SymbolInterner>(&self, interner: &mut I) -> OwnedExpression<I::Symbol> {
         match self {
-            Expression::Number(n) => OwnedExpression::Number(*n),
+            Expression::Integer(n) => OwnedExpression::Integer(*n),
+            Expression::Float(n) => OwnedExpression::Float(*n),
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(n) => OwnedExpression::BigInt(n.clone()),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(r) => OwnedExpression::Ratio(r.clone()),
             Expression::Bool(b) => OwnedExpression::Bool(*b),
             Expression::Str(s) => OwnedExpression::Str(s.to_string()),
-            Expression::Symbol(s) => OwnedExpression::Symbol(S::from_str(s)),
+            Expression::Char(c) => OwnedExpression::Char(*c),
+            Expression::Symbol(s) => OwnedExpression::Symbol(interner.intern(s)),
+            Expression::Keyword(k) => OwnedExpression::Keyword(interner.intern(k)),
             Expression::List(list) => OwnedExpression::List(
-                list.iter().map(|expr| expr.to_owned::<S>()).collect()
+                list.iter().map(|expr| expr.to_owned_with(interner)).collect()
+            ),
+            Expression::DottedList(list, tail) => OwnedExpression::DottedList(
+                list.iter().map(|expr| expr.to_owned_with(interner)).collect(),
+                Box::new(tail.to_owned_with(interner)),
+            ),
+            Expression::Vector(items) => OwnedExpression::Vector(
+                items.iter().map(|expr| expr.to_owned_with(interner)).collect()
             ),
+            Expression::Map(entries) => OwnedExpression::Map(
+                entries.iter().map(|(key, value)| (key.to_owned_with(interner), value.to_owned_with(interner))).collect()
+            ),
+            Expression::Set(items) => OwnedExpression::Set(
+                items.iter().map(|expr| expr.to_owned_with(interner)).collect()
+            ),
+            Expression::Bytes(bytes) => OwnedExpression::Bytes(bytes.to_vec()),
+            Expression::Hinted(hint, value) => OwnedExpression::Hinted(
+                Box::new(hint.to_owned_with(interner)),
+                Box::new(value.to_owned_with(interner)),
+            ),
+            Expression::Quoted(kind, expr) => OwnedExpression::Quoted(*kind, Box::new(expr.to_owned_with(interner))),
             Expression::Null => OwnedExpression::Null,
         }
     }
 }
 
-/// Optimized zero-copy tokenizer using string slices.
-/// 
-/// This function efficiently tokenizes S-expression source code by:
-/// - Pre-allocating vectors with realistic capacity estimates
-/// - Using efficient string operations instead of character-by-character iteration
-/// - Minimizing memory allocations through zero-copy string slices
-/// 
-/// # Arguments
-/// 
-/// * `src` - The source string to tokenize
-/// 
-/// # Returns
+/// The interner behind [`Expression::to_owned`], which creates each symbol
+/// with [`OwnedSymbol::from_str`].
+struct FromStrInterner<S>(PhantomData<S>);
+
+impl<S: OwnedSymbol> SymbolInterner for FromStrInterner<S> {
+    type Symbol = S;
+
+    fn intern(&mut self, name: &str) -> S {
+        S::from_str(name)
+    }
+}
+
+impl<S: OwnedSymbol + AsRef<str>> OwnedExpression<S> {
+    /// Borrow this expression as an [`Expression`], sharing its strings.
+    pub(crate) fn borrowed(&self) -> Expression<'_> {
+        match self {
+            OwnedExpression::Integer(n) => Expression::Integer(*n),
+            OwnedExpression::Float(n) => Expression::Float(*n),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::BigInt(n) => Expression::BigInt(n.clone()),
+            #[cfg(feature = "bignum")]
+            OwnedExpression::Ratio(r) => Expression::Ratio(r.clone()),
+            OwnedExpression::Bool(b) => Expression::Bool(*b),
+            OwnedExpression::Str(s) => Expression::Str(Cow::Borrowed(s)),
+            OwnedExpression::Char(c) => Expression::Char(*c),
+            OwnedExpression::Symbol(s) => Expression::Symbol(Cow::Borrowed(s.as_ref())),
+            OwnedExpression::Keyword(s) => Expression::Keyword(Cow::Borrowed(s.as_ref())),
+            OwnedExpression::List(items) => Expression::List(items.iter().map(Self::borrowed).collect()),
+            OwnedExpression::DottedList(items, tail) => {
+                Expression::DottedList(items.iter().map(Self::borrowed).collect(), Box::new(tail.borrowed()))
+            }
+            OwnedExpression::Vector(items) => Expression::Vector(items.iter().map(Self::borrowed).collect()),
+            OwnedExpression::Map(entries) => {
+                Expression::Map(entries.iter().map(|(key, value)| (key.borrowed(), value.borrowed())).collect())
+            }
+            OwnedExpression::Set(items) => Expression::Set(items.iter().map(Self::borrowed).collect()),
+            OwnedExpression::Bytes(bytes) => Expression::Bytes(Cow::Borrowed(bytes)),
+            OwnedExpression::Hinted(hint, value) => Expression::Hinted(Box::new(hint.borrowed()), Box::new(value.borrowed())),
+            OwnedExpression::Quoted(kind, inner) => Expression::Quoted(*kind, Box::new(inner.borrowed())),
+            OwnedExpression::Null => Expression::Null,
+        }
+    }
+}
+
+/// Optimized zero-copy recursive descent parser.
 /// 
-/// A vector of string slices representing the tokens
-fn tokenize(src: &str) -> Vec<&str> {
-    // More realistic capacity estimate
-    let mut tokens = Vec::with_capacity(src.len() / 2);
-    let mut current = src;
-    
-    while !current.is_empty() {
-        // Skip leading whitespace efficiently
-        current = current.trim_start();
-        if current.is_empty() { break; }
-        
-        // Find next delimiter or whitespace
-        let (token, rest) = match current.find(|c: char| c.is_whitespace() || "()'".contains(c)) {
-            Some(pos) => {
-                let token = &current[..pos];
-                let rest = &current[pos..];
-                (token, rest)
+/// The parser pulls tokens from a [`Lexer`] on demand with a single token of
+/// lookahead, so no token vector is ever materialized. Comments are skipped
+/// as they are scanned. When requested, the parser also records the source
+/// span of every node it produces into a [`SpanTree`] that mirrors the
+/// resulting expression. Span collection is off for plain [`read`] so the
+/// common path pays nothing for it.
+///
+/// The nodes themselves are made by a [`Builder`], so trees other than
+/// [`Expression`] are read with the same syntax and errors.
+#[derive(Debug)]
+pub(crate) struct Parser<'a, B: Builder<'a> = ExpressionBuilder> {
+    lexer: Lexer<'a>,
+    options: ReaderOptions,
+    /// The lookahead token, if one has been scanned but not consumed
+    peeked: Option<Token<'a>>,
+    /// Completed span nodes at the current nesting level, if tracking spans
+    spans: Option<Vec<SpanTree>>,
+    /// Kept comments that have not been attached to a node yet
+    comments: Vec<Comment>,
+    builder: B,
+    /// Finished elements of the collections being parsed, innermost last
+    stack: Vec<B::Node>,
+}
+
+impl<'a, B: Builder<'a> + Clone> Clone for Parser<'a, B> {
+    /// Clone the parser between expressions.
+    ///
+    /// The scratch stack only holds elements while a collection is being
+    /// parsed, so the clone starts with an empty one.
+    fn clone(&self) -> Self {
+        Parser {
+            lexer: self.lexer.clone(),
+            options: self.options.clone(),
+            peeked: self.peeked,
+            spans: self.spans.clone(),
+            comments: self.comments.clone(),
+            builder: self.builder.clone(),
+            stack: Vec::with_capacity(64),
+        }
+    }
+}
+
+/// Makes the nodes of the tree a [`Parser`] reads.
+///
+/// Collection elements are handed over as a drain of the parser's scratch
+/// stack, in source order; map elements alternate between keys and values.
+pub(crate) trait Builder<'a> {
+    /// The node type of the tree
+    type Node;
+
+    /// Make a node from an atom, string or character.
+    fn leaf(&mut self, expr: Expression<'a>) -> Self::Node;
+
+    /// Returns `true` if `node` is an integer that fits in a byte.
+    fn is_byte(&self, node: &Self::Node) -> bool;
+
+    /// Make a list.
+    fn list(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make an improper list.
+    fn dotted_list(&mut self, items: Drain<'_, Self::Node>, tail: Self::Node) -> Self::Node;
+
+    /// Make a vector.
+    fn vector(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make a map from an even number of keys and values.
+    fn map(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make a set.
+    fn set(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make a bytevector from elements checked with
+    /// [`is_byte`](Builder::is_byte).
+    fn bytes(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make a display hint from exactly two elements, the hint and the value.
+    fn hinted(&mut self, items: Drain<'_, Self::Node>) -> Self::Node;
+
+    /// Make a [`QuoteStyle::Variant`] quoted node.
+    fn quoted(&mut self, kind: QuoteKind, expr: Self::Node) -> Self::Node;
+}
+
+/// Builds [`Expression`] trees.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ExpressionBuilder;
+
+impl<'a> Builder<'a> for ExpressionBuilder {
+    type Node = Expression<'a>;
+
+    fn leaf(&mut self, expr: Expression<'a>) -> Expression<'a> {
+        expr
+    }
+
+    fn is_byte(&self, node: &Expression<'a>) -> bool {
+        matches!(node, Expression::Integer(0..=255))
+    }
+
+    fn list(&mut self, items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        Expression::List(items.collect())
+    }
+
+    fn dotted_list(&mut self, items: Drain<'_, Expression<'a>>, tail: Expression<'a>) -> Expression<'a> {
+        Expression::DottedList(items.collect(), Box::new(tail))
+    }
+
+    fn vector(&mut self, items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        Expression::Vector(items.collect())
+    }
+
+    fn map(&mut self, mut items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        let mut entries = Vec::with_capacity(items.len() / 2);
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            entries.push((key, value));
+        }
+        Expression::Map(entries)
+    }
+
+    fn set(&mut self, items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        Expression::Set(items.collect())
+    }
+
+    fn bytes(&mut self, items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        Expression::Bytes(
+            items.map(|item| match item {
+                Expression::Integer(n) => n as u8,
+                _ => unreachable!("bytevector elements are checked as they are read"),
+            }).collect()
+        )
+    }
+
+    fn hinted(&mut self, mut items: Drain<'_, Expression<'a>>) -> Expression<'a> {
+        match (items.next(), items.next()) {
+            (Some(hint), Some(value)) => Expression::Hinted(Box::new(hint), Box::new(value)),
+            _ => unreachable!("display hints are checked as they are read"),
+        }
+    }
+
+    fn quoted(&mut self, kind: QuoteKind, expr: Expression<'a>) -> Expression<'a> {
+        Expression::Quoted(kind, Box::new(expr))
+    }
+}
+
+impl<'a> Parser<'a> {
+    /// Create a parser over `src`.
+    fn new(src: &'a str, options: &ReaderOptions) -> Self {
+        Self::with_builder(src, options, ExpressionBuilder)
+    }
+
+    /// Parse the next expression, recording its span tree.
+    fn parse_spanned(&mut self) -> Result<Spanned<Expression<'a>>, ParseError> {
+        let outer = self.spans.replace(Vec::new());
+        let result = self.parse();
+        let spans = std::mem::replace(&mut self.spans, outer)
+            .and_then(|mut spans| spans.pop())
+            .unwrap_or_default();
+        result.map(|expr| Spanned { expr, spans })
+    }
+}
+
+impl<'a, B: Builder<'a>> Parser<'a, B> {
+    /// Create a parser over `src` whose nodes are made by `builder`.
+    pub(crate) fn with_builder(src: &'a str, options: &ReaderOptions, builder: B) -> Self {
+        Parser {
+            lexer: options.lexer(src),
+            options: options.clone(),
+            peeked: None,
+            spans: None,
+            comments: Vec::new(),
+            builder,
+            stack: Vec::with_capacity(64),
+        }
+    }
+
+    /// Look at the next token without consuming it.
+    fn peek(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        if self.peeked.is_none() {
+            self.peeked = self.fetch()?;
+        }
+        Ok(self.peeked)
+    }
+
+    /// Consume the next token.
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.fetch(),
+        }
+    }
+
+    /// Scan the next token that is not part of a comment.
+    /// 
+    /// Datum comments are resolved here by parsing and discarding the
+    /// expression that follows `#;`.
+    fn fetch(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        loop {
+            let token = match self.lexer.next_token()? {
+                Some(token) => token,
+                None => return Ok(None),
+            };
+            let kind = match token.kind {
+                TokenKind::LineComment => CommentKind::Line,
+                TokenKind::BlockComment => CommentKind::Block,
+                TokenKind::DatumComment => {
+                    self.skip_datum()?;
+                    CommentKind::Datum
+                }
+                _ => return Ok(Some(token)),
+            };
+            if self.options.keep_comments {
+                let span = token.span.join(self.lexer.position());
+                self.comments.push(Comment { kind, span });
             }
-            None => (current, ""),
+        }
+    }
+
+    /// Parse and discard the expression commented out by `#;`.
+    fn skip_datum(&mut self) -> Result<(), ParseError> {
+        let spans = self.spans.take();
+        let comments = std::mem::take(&mut self.comments);
+        let result = self.parse();
+        self.spans = spans;
+        self.comments = comments;
+        result.map(drop)
+    }
+
+    /// Optimized zero-copy parser with proper error handling.
+    /// 
+    /// This method parses the next expression from the token stream, using:
+    /// - Pre-allocated vectors for common list sizes
+    /// - Proper error handling instead of panics
+    /// - Recursive descent parsing with zero-copy semantics
+    /// 
+    /// # Returns
+    /// 
+    /// A `Result` containing either the parsed expression or a parse error
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `ParseError` variants for various parsing failures
+    pub(crate) fn parse(&mut self) -> Result<B::Node, ParseError> {
+        let token = match self.next_token()? {
+            Some(token) => token,
+            None => return Err(ParseError::UnexpectedEOF { span: self.position() }),
         };
         
-        if !token.is_empty() {
-            tokens.push(token);
-        }
+        let leading = std::mem::take(&mut self.comments);
         
-        // Handle delimiter efficiently
-        if !rest.is_empty() {
-            let delimiter = &rest[..1];
-            if "()'".contains(delimiter) {
-                tokens.push(delimiter);
+        match token.kind {
+            TokenKind::LParen
+            | TokenKind::LBracket
+            | TokenKind::LBrace
+            | TokenKind::HashLBrace
+            | TokenKind::HashLBracket
+            | TokenKind::HashU8LParen => {
+                self.parse_collection(&token, leading)
+            }
+            TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace => {
+                let found = token.text.chars().next().unwrap_or(')');
+                Err(ParseError::UnexpectedClosingParen { span: token.span, found })
+            }
+            TokenKind::Str => {
+                self.push_leaf(token.span, leading);
+                let s = unescape(&token)?;
+                Ok(self.builder.leaf(Expression::Str(s)))
+            }
+            TokenKind::Verbatim => {
+                self.push_leaf(token.span, leading);
+                Ok(self.builder.leaf(Expression::Str(Cow::Borrowed(verbatim_contents(&token)))))
+            }
+            TokenKind::Char => {
+                self.push_leaf(token.span, leading);
+                let c = parse_char(&token)?;
+                Ok(self.builder.leaf(Expression::Char(c)))
+            }
+            TokenKind::Quote => self.parse_quoted(QuoteKind::Quote, &token, leading),
+            TokenKind::Quasiquote => self.parse_quoted(QuoteKind::Quasiquote, &token, leading),
+            TokenKind::Unquote => self.parse_quoted(QuoteKind::Unquote, &token, leading),
+            TokenKind::UnquoteSplicing => self.parse_quoted(QuoteKind::UnquoteSplicing, &token, leading),
+            TokenKind::Atom => {
+                self.push_leaf(token.span, leading);
+                let expr = parse_atom(&token, &self.options)?;
+                Ok(self.builder.leaf(expr))
+            }
+            TokenKind::LineComment | TokenKind::BlockComment | TokenKind::DatumComment => {
+                unreachable!("comments are skipped by Parser::fetch")
             }
-            current = &rest[1..];
-        } else {
-            break;
         }
     }
-    
-    tokens
+
+    /// Parse a list, vector, map, set or bytevector after its opening delimiter.
+    /// 
+    /// The span tree of the collection has one child per element; for maps
+    /// the children are the keys and values in source order, and for dotted
+    /// lists the elements followed by the tail.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `ParseError::MismatchedBracket` if the collection is closed
+    /// with the wrong delimiter, `ParseError::MissingClosingParen` or
+    /// `ParseError::MissingClosingBracket` if it is not closed at all,
+    /// `ParseError::UnpairedMapKey` for a map with an odd number of elements,
+    /// `ParseError::InvalidByte` for a bytevector element that is not a byte,
+    /// `ParseError::InvalidDisplayHint` for a display hint without exactly
+    /// two elements,
+    /// and `ParseError::MisplacedDot` or `ParseError::ExtraAfterDottedTail`
+    /// for malformed dotted lists
+    fn parse_collection(&mut self, open: &Token<'a>, leading: Vec<Comment>) -> Result<B::Node, ParseError> {
+        let (close_kind, expected) = match open.kind {
+            TokenKind::LParen | TokenKind::HashU8LParen => (TokenKind::RParen, ')'),
+            TokenKind::LBracket | TokenKind::HashLBracket => (TokenKind::RBracket, ']'),
+            _ => (TokenKind::RBrace, '}'),
+        };
+        let mark = self.spans.as_ref().map_or(0, Vec::len);
+        let start = self.stack.len();
+        let mut tail = None;
+        let dotted = open.kind == TokenKind::LParen && self.options.dotted_pairs;
+        let close = loop {
+            match self.peek()? {
+                Some(next) if next.kind == close_kind => break next,
+                Some(dot) if dotted && is_dot(&dot) => {
+                    if self.stack.len() == start || tail.is_some() {
+                        return Err(ParseError::MisplacedDot { span: dot.span, token: dot.text.to_string() });
+                    }
+                    self.peeked = None;
+                    match self.peek()? {
+                        Some(next) if is_closing(next.kind) || is_dot(&next) => {
+                            return Err(ParseError::MisplacedDot { span: dot.span, token: dot.text.to_string() });
+                        }
+                        Some(_) => tail = Some(self.parse()?),
+                        None => {}
+                    }
+                }
+                Some(next) if is_closing(next.kind) => {
+                    let found = next.text.chars().next().unwrap_or(expected);
+                    return Err(ParseError::MismatchedBracket { span: next.span, open: open.span, expected, found });
+                }
+                Some(next) if tail.is_some() => {
+                    return Err(ParseError::ExtraAfterDottedTail { span: next.span, token: next.text.to_string() });
+                }
+                Some(next) => {
+                    let item = self.parse()?;
+                    if open.kind == TokenKind::HashU8LParen && !self.builder.is_byte(&item) {
+                        return Err(ParseError::InvalidByte { span: next.span, token: next.text.to_string() });
+                    }
+                    self.stack.push(item);
+                }
+                None => {
+                    let span = self.position();
+                    return Err(match open.kind {
+                        TokenKind::LParen | TokenKind::HashU8LParen => ParseError::MissingClosingParen { span, open: open.span },
+                        _ => ParseError::MissingClosingBracket { span, open: open.span, expected },
+                    });
+                }
+            }
+        };
+        self.peeked = None; // Skip closing delimiter
+        let span = open.span.join(close.span);
+        if open.kind == TokenKind::LBrace && !(self.stack.len() - start).is_multiple_of(2) {
+            return Err(ParseError::UnpairedMapKey { span });
+        }
+        if open.kind == TokenKind::HashLBracket && self.stack.len() - start != 2 {
+            return Err(ParseError::InvalidDisplayHint { span });
+        }
+        let trailing = std::mem::take(&mut self.comments);
+        if let Some(spans) = &mut self.spans {
+            let children = spans.split_off(mark);
+            spans.push(SpanTree { span, children, leading, trailing });
+        }
+
+        let items = self.stack.drain(start..);
+        Ok(match open.kind {
+            TokenKind::LParen => match tail {
+                Some(tail) => self.builder.dotted_list(items, tail),
+                None => self.builder.list(items),
+            },
+            TokenKind::LBracket => self.builder.vector(items),
+            TokenKind::LBrace => self.builder.map(items),
+            TokenKind::HashU8LParen => self.builder.bytes(items),
+            TokenKind::HashLBracket => self.builder.hinted(items),
+            _ => self.builder.set(items),
+        })
+    }
+
+    /// Parse the expression following a quote reader macro.
+    /// 
+    /// Depending on [`QuoteStyle`], this builds either the list form
+    /// `(quote x)`, whose span tree has the prefix and `x` as children, or an
+    /// [`Expression::Quoted`] node whose span tree has `x` as its only child.
+    fn parse_quoted(
+        &mut self,
+        kind: QuoteKind,
+        prefix: &Token<'a>,
+        leading: Vec<Comment>,
+    ) -> Result<B::Node, ParseError> {
+        let expr = self.parse()?;
+        let style = self.options.quote_style;
+        if let Some(spans) = &mut self.spans {
+            let inner = spans.pop().unwrap_or_default();
+            let span = prefix.span.join(inner.span);
+            let children = match style {
+                QuoteStyle::List => vec![SpanTree::leaf(prefix.span), inner],
+                QuoteStyle::Variant => vec![inner],
+            };
+            spans.push(SpanTree { span, children, leading, trailing: Vec::new() });
+        }
+        Ok(match style {
+            QuoteStyle::List => {
+                let symbol = self.builder.leaf(Expression::Symbol(Cow::Borrowed(kind.symbol())));
+                let start = self.stack.len();
+                self.stack.extend([symbol, expr]);
+                self.builder.list(self.stack.drain(start..))
+            }
+            QuoteStyle::Variant => self.builder.quoted(kind, expr),
+        })
+    }
+
+    /// Returns `true` once only whitespace is left in the input.
+    pub(crate) fn is_at_end(&mut self) -> Result<bool, ParseError> {
+        Ok(self.peek()?.is_none())
+    }
+
+    /// Fail with [`ParseError::TrailingInput`] unless the input is exhausted.
+    fn expect_end(&mut self) -> Result<(), ParseError> {
+        match self.peek()? {
+            Some(token) => Err(ParseError::TrailingInput { span: token.span, token: token.text.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    /// The (empty) span at the current end of the scanned input.
+    fn position(&self) -> Span {
+        self.lexer.position()
+    }
+
+    /// Record the span of an atom, if spans are being tracked.
+    fn push_leaf(&mut self, span: Span, leading: Vec<Comment>) {
+        if let Some(spans) = &mut self.spans {
+            spans.push(SpanTree { leading, ..SpanTree::leaf(span) });
+        }
+    }
 }
 
-/// Optimized zero-copy parser with proper error handling.
-/// 
-/// This function parses a slice of tokens into an S-expression, using:
-/// - Pre-allocated vectors for common list sizes
-/// - Proper error handling instead of panics
-/// - Recursive descent parsing with zero-copy semantics
-/// 
-/// # Arguments
+/// Streaming reader over the top-level forms of a source string.
 /// 
-/// * `tokens` - A mutable reference to a slice of tokens to parse
+/// `Reader` lazily yields one expression at a time, scanning only as much of
+/// the source as the current form needs. This lets REPLs and batch tools
+/// start processing the first form before the rest of the input has been
+/// looked at.
 /// 
-/// # Returns
+/// The iterator stops after the first error, since the position of any
+/// following form is unknown once the input is malformed.
 /// 
-/// A `Result` containing either the parsed expression or a parse error
+/// # Examples
 /// 
-/// # Errors
+/// ```rust
+/// use sexpression::{Reader, Expression};
 /// 
-/// Returns `ParseError` variants for various parsing failures
-fn parse<'a>(tokens: &mut &[&'a str]) -> Result<Expression<'a>, ParseError> {
-    if tokens.is_empty() {
-        return Err(ParseError::UnexpectedEOF);
+/// let mut reader = Reader::new("(define x 42) (print x) (oops");
+/// assert!(matches!(reader.next(), Some(Ok(Expression::List(_)))));
+/// assert!(matches!(reader.next(), Some(Ok(Expression::List(_)))));
+/// assert!(matches!(reader.next(), Some(Err(_))));
+/// assert!(reader.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    parser: Parser<'a>,
+    failed: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// Create a reader over the given source string.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `src` - The source string containing zero or more S-expressions
+    pub fn new(src: &'a str) -> Self {
+        Self::with_options(src, &ReaderOptions::default())
     }
-    
-    let token = tokens[0];
-    *tokens = &tokens[1..]; // Advance slice
-    
-    match token {
-        "(" => {
-            // Pre-allocate list vector for common list sizes
-            let mut stack = Vec::with_capacity(8);
-            while !tokens.is_empty() && tokens[0] != ")" {
-                stack.push(parse(tokens)?);
-            }
-            if tokens.is_empty() {
-                return Err(ParseError::MissingClosingParen);
-            }
-            *tokens = &tokens[1..]; // Skip closing paren
-            Ok(Expression::List(stack))
+
+    /// Create a reader with custom [`ReaderOptions`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `src` - The source string containing zero or more S-expressions
+    /// * `options` - The reader configuration
+    pub fn with_options(src: &'a str, options: &ReaderOptions) -> Self {
+        Reader { parser: Parser::new(src, options), failed: false }
+    }
+
+    /// Comments after the last form of the input.
+    /// 
+    /// These have no following expression to attach to. The list is only
+    /// complete once the reader has returned `None`, and is always empty
+    /// unless [`ReaderOptions::keep_comments`] is enabled.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use sexpression::{Reader, ReaderOptions};
+    /// 
+    /// let options = ReaderOptions::new().keep_comments(true);
+    /// let mut reader = Reader::with_options("(main) ; end of file", &options);
+    /// assert_eq!(reader.by_ref().count(), 1);
+    /// assert_eq!(reader.trailing_comments().len(), 1);
+    /// ```
+    pub fn trailing_comments(&self) -> &[Comment] {
+        &self.parser.comments
+    }
+
+    /// Read the next top-level form together with its source spans.
+    /// 
+    /// This can be freely interleaved with [`Iterator::next`].
+    /// 
+    /// # Returns
+    /// 
+    /// `None` at the end of input or after an error, otherwise the next
+    /// spanned expression or the error encountered while reading it
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use sexpression::Reader;
+    /// 
+    /// let src = "(a)\n(b c)";
+    /// let mut reader = Reader::new(src);
+    /// reader.next();
+    /// let second = reader.next_spanned().unwrap().unwrap();
+    /// assert_eq!(second.span().line, 2);
+    /// assert_eq!(second.span().source_text(src), "(b c)");
+    /// ```
+    pub fn next_spanned(&mut self) -> Option<Result<Spanned<Expression<'a>>, ParseError>> {
+        self.read_next(Parser::parse_spanned)
+    }
+
+    /// Run `parse` unless the input is exhausted, fusing on errors.
+    fn read_next<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Parser<'a>) -> Result<T, ParseError>,
+    ) -> Option<Result<T, ParseError>> {
+        if self.failed {
+            return None;
         }
-        ")" => Err(ParseError::UnexpectedClosingParen),
-        _ => Ok(parse_atom(token)),
+        let result = match self.parser.is_at_end() {
+            Ok(true) => return None,
+            Ok(false) => parse(&mut self.parser),
+            Err(e) => Err(e),
+        };
+        self.failed = result.is_err();
+        Some(result)
     }
 }
 
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Expression<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_next(Parser::parse)
+    }
+}
+
+/// Returns `true` for tokens that close a list, vector, map or set.
+fn is_closing(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace)
+}
+
+/// Returns `true` for the `.` separating a dotted list from its tail.
+fn is_dot(token: &Token<'_>) -> bool {
+    token.kind == TokenKind::Atom && token.text == "."
+}
+
 /// Optimized atom parsing with fast paths.
 /// 
 /// This function parses individual tokens into atomic expressions using:
-/// - Fast-path checks for single-character symbols
+/// - Fast-path checks for single-character tokens
 /// - Optimized number parsing with first-character checks
-/// - Bounds-safe string literal handling
+/// 
+/// Numbers are classified by their syntax: a run of digits with an optional
+/// sign is an [`Expression::Integer`], while a literal with a fractional part
+/// or exponent is an [`Expression::Float`]. Radix prefixes, digit separators
+/// and special floats are accepted as configured by
+/// [`NumberSyntax`](crate::NumberSyntax). With the `bignum` feature, integers
+/// beyond `i64` become `BigInt`s and `n/d` literals become exact `Ratio`s.
+/// Booleans, null and keywords are recognized as configured by
+/// [`ReaderOptions`]. String
+/// literals never reach this function; the lexer scans them as separate
+/// tokens.
 /// 
 /// # Arguments
 /// 
-/// * `token` - The token string to parse as an atom
+/// * `token` - The token to parse as an atom
+/// * `options` - The reader configuration, e.g. the accepted number syntax
 /// 
 /// # Returns
 /// 
-/// The parsed atomic expression
-fn parse_atom(token: &str) -> Expression {
-    // Fast path: single character symbols
-    if token.len() == 1 {
-        return Expression::Symbol(token);
+/// A `Result` containing the parsed atomic expression
+/// 
+/// # Errors
+/// 
+/// Returns `ParseError::IntegerOverflow` for integers that do not fit in an
+/// `i64` (without the `bignum` feature), `ParseError::InvalidNumber` for
+/// ratios with a zero denominator, and
+/// `ParseError::InvalidEscape` for bad escapes inside `|...|`
+fn parse_atom<'a>(token: &Token<'a>, options: &ReaderOptions) -> Result<Expression<'a>, ParseError> {
+    let text = token.text;
+    
+    // Atoms with `|...|` sections are always symbols or keywords
+    if text.contains('|') {
+        return parse_quoted_symbol(token, options.keyword_style);
+    }
+    
+    // Fast path: single digit tokens
+    if let [b @ b'0'..=b'9'] = text.as_bytes() {
+        if options.numbers {
+            return Ok(Expression::Integer(i64::from(b - b'0')));
+        }
     }
     
     // Fast path: check first character for number parsing
-    if let Some(first) = token.chars().next() {
-        if first.is_ascii_digit() || first == '-' || first == '+' {
-            if let Ok(n) = token.parse::<f64>() {
-                return Expression::Number(n);
+    if let Some(first) = text.chars().next() {
+        if options.numbers && (first.is_ascii_digit() || matches!(first, '-' | '+' | '.' | '#')) {
+            if let Some(number) = parse_number(token, &options.number_syntax)? {
+                return Ok(number);
             }
         }
     }
     
-    // Check for booleans and null
-    match token {
-        "true" => return Expression::Bool(true),
-        "false" => return Expression::Bool(false),
-        "null" => return Expression::Null,
-        _ => {}
+    // Check for the dialect's booleans and null
+    let is_any = |words: &[Cow<'static, str>]| words.iter().any(|word| word == text);
+    if is_any(&options.true_literals) {
+        return Ok(Expression::Bool(true));
     }
-    
-    // Optimized string literal handling with bounds safety
-    if token.len() >= 2 && token.starts_with('"') && token.ends_with('"') {
-        if let Some(content) = token.get(1..token.len()-1) {
-            return Expression::Str(content);
-        }
+    if is_any(&options.false_literals) {
+        return Ok(Expression::Bool(false));
     }
-    
-    // Default to symbol
-    Expression::Symbol(token)
+    if is_any(&options.null_literals) {
+        return Ok(Expression::Null);
+    }
+    Ok(match options.keyword_style {
+        KeywordStyle::Prefix if text.len() > 1 && text.starts_with(':') => Expression::Keyword(Cow::Borrowed(&text[1..])),
+        KeywordStyle::Suffix if text.len() > 1 && text.ends_with(':') => {
+            Expression::Keyword(Cow::Borrowed(&text[..text.len() - 1]))
+        }
+        // Default to symbol
+        _ => Expression::Symbol(Cow::Borrowed(text)),
+    })
+}
+
+/// Parse an atom containing `|...|` sections as a symbol or keyword.
+/// 
+/// A keyword colon only counts outside the bars, so `:|a b|` is the keyword
+/// `a b` while `|:a|` is the symbol `:a`.
+fn parse_quoted_symbol<'a>(token: &Token<'a>, style: KeywordStyle) -> Result<Expression<'a>, ParseError> {
+    let name = unescape_symbol(token)?;
+    Ok(match style {
+        KeywordStyle::Prefix if token.text.starts_with(':') => {
+            let mut name = name.into_owned();
+            name.remove(0);
+            Expression::Keyword(Cow::Owned(name))
+        }
+        KeywordStyle::Suffix if token.text.ends_with(':') => {
+            let mut name = name.into_owned();
+            name.pop();
+            Expression::Keyword(Cow::Owned(name))
+        }
+        _ => Expression::Symbol(name),
+    })
 }
 
 /// Main parsing function with error handling.
@@ -458,10 +1413,166 @@ fn parse_atom(token: &str) -> Expression {
 /// let result = read("(unclosed");
 /// assert!(result.is_err());
 /// ```
-pub fn read(src: &str) -> Result<Expression, ParseError> {
-    let tokens = tokenize(src);
-    let mut token_slice = tokens.as_slice();
-    parse(&mut token_slice)
+pub fn read(src: &str) -> Result<Expression<'_>, ParseError> {
+    Parser::new(src, &ReaderOptions::default()).parse()
+}
+
+/// Parse an S-expression using custom options.
+///
+/// This works like [`read`], but honours [`ReaderOptions`] such as a
+/// [`Dialect`](crate::Dialect) preset.
+///
+/// # Arguments
+///
+/// * `src` - The source string to parse as an S-expression
+/// * `options` - The reader configuration
+///
+/// # Returns
+///
+/// A `Result` containing either the parsed expression or a parse error
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read_with, Dialect, Expression, ReaderOptions};
+///
+/// let options = ReaderOptions::new().dialect(Dialect::Clojure);
+/// let expr = read_with("{:a nil, :b [1 2]}", &options).unwrap();
+/// assert_eq!(expr.to_string(), "{:a null :b [1 2]}");
+/// ```
+pub fn read_with<'a>(src: &'a str, options: &ReaderOptions) -> Result<Expression<'a>, ParseError> {
+    Parser::new(src, options).parse()
+}
+
+/// Strict parsing function that rejects trailing input.
+/// 
+/// This works like [`read`], but instead of silently ignoring anything after
+/// the first complete expression it reports it as an error. Use it when the
+/// source is expected to contain exactly one form.
+/// 
+/// # Arguments
+/// 
+/// * `src` - The source string to parse as a single S-expression
+/// 
+/// # Returns
+/// 
+/// A `Result` containing either the parsed expression or a parse error
+/// 
+/// # Errors
+/// 
+/// Returns `ParseError::TrailingInput` pointing at the first extra token
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use sexpression::{read, read_strict, ParseError};
+/// 
+/// assert!(read("(a) (b)").is_ok());
+/// assert!(matches!(read_strict("(a) (b)"), Err(ParseError::TrailingInput { .. })));
+/// assert!(read_strict("  (a)  ").is_ok());
+/// ```
+pub fn read_strict(src: &str) -> Result<Expression<'_>, ParseError> {
+    read_strict_with(src, &ReaderOptions::default())
+}
+
+/// Strict parsing with custom options, rejecting trailing input.
+pub(crate) fn read_strict_with<'a>(src: &'a str, options: &ReaderOptions) -> Result<Expression<'a>, ParseError> {
+    let mut parser = Parser::new(src, options);
+    let expr = parser.parse()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+/// Parse every top-level S-expression in the source.
+/// 
+/// Source files are usually a sequence of forms rather than a single
+/// expression. This function reads all of them in order, returning an empty
+/// vector for input that contains only whitespace.
+/// 
+/// # Arguments
+/// 
+/// * `src` - The source string containing zero or more S-expressions
+/// 
+/// # Returns
+/// 
+/// A `Result` containing either all parsed expressions or the first parse error
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use sexpression::{read_all, Expression};
+/// 
+/// let forms = read_all("(define x 1)\n(define y 2)\nx").unwrap();
+/// assert_eq!(forms.len(), 3);
+/// assert_eq!(forms[2], Expression::Symbol("x".into()));
+/// ```
+pub fn read_all(src: &str) -> Result<Vec<Expression<'_>>, ParseError> {
+    Reader::new(src).collect()
+}
+
+/// Parse an S-expression together with its source spans.
+/// 
+/// This works like [`read`], but additionally records the byte range, line
+/// and column of every node in a [`SpanTree`] that mirrors the expression.
+/// Use it when diagnostics need to point back into the original source.
+/// 
+/// # Arguments
+/// 
+/// * `src` - The source string to parse as an S-expression
+/// 
+/// # Returns
+/// 
+/// A `Result` containing either the spanned expression or a parse error
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use sexpression::{read_spanned, Expression};
+/// 
+/// let src = "(square\n  x)";
+/// let spanned = read_spanned(src).unwrap();
+/// assert_eq!(spanned.span().source_text(src), src);
+/// 
+/// let x = spanned.spans.children[1].span;
+/// assert_eq!(x.source_text(src), "x");
+/// assert_eq!((x.line, x.column), (2, 3));
+/// ```
+pub fn read_spanned(src: &str) -> Result<Spanned<Expression<'_>>, ParseError> {
+    read_spanned_with(src, &ReaderOptions::default())
+}
+
+/// Parse an S-expression with its source spans using custom options.
+/// 
+/// This works like [`read_spanned`], but honours [`ReaderOptions`] such as
+/// keeping comments as trivia on the returned [`SpanTree`].
+/// 
+/// # Arguments
+/// 
+/// * `src` - The source string to parse as an S-expression
+/// * `options` - The reader configuration
+/// 
+/// # Returns
+/// 
+/// A `Result` containing either the spanned expression or a parse error
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use sexpression::{read_spanned_with, CommentKind, ReaderOptions};
+/// 
+/// let src = "(a ; first\n b #| last |#)";
+/// let options = ReaderOptions::new().keep_comments(true);
+/// let spanned = read_spanned_with(src, &options).unwrap();
+/// 
+/// let b = &spanned.spans.children[1];
+/// assert_eq!(b.leading[0].span.source_text(src), "; first");
+/// assert_eq!(spanned.spans.trailing[0].kind, CommentKind::Block);
+/// ```
+pub fn read_spanned_with<'a>(
+    src: &'a str,
+    options: &ReaderOptions,
+) -> Result<Spanned<Expression<'a>>, ParseError> {
+    Parser::new(src, options).parse_spanned()
 }
 
 /// Convenience function for backward compatibility (panics on error).
@@ -490,18 +1601,23 @@ pub fn read(src: &str) -> Result<Expression, ParseError> {
 /// let expr = read_unchecked("(hello world)");
 /// // Use expr safely knowing it was parsed successfully
 /// ```
-pub fn read_unchecked(src: &str) -> Expression {
+pub fn read_unchecked(src: &str) -> Expression<'_> {
     read(src).expect("Failed to parse S-expression")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::options::Dialect;
 
     #[test]
     fn tokenize_test() {
-        assert_eq!(tokenize("this is a test"), vec!["this", "is", "a", "test"]);
-        assert_eq!(tokenize("(hello world)"), vec!["(", "hello", "world", ")"]);
+        let texts = |src| -> Vec<&str> {
+            Lexer::new(src).map(|t| t.unwrap().text).collect()
+        };
+        assert_eq!(texts("this is a test"), vec!["this", "is", "a", "test"]);
+        assert_eq!(texts("(hello world)"), vec!["(", "hello", "world", ")"]);
+        assert_eq!(texts("(say \"hello world\")"), vec!["(", "say", "\"hello world\"", ")"]);
     }
 
     #[test]
@@ -514,19 +1630,541 @@ mod tests {
         assert!(read(")unexpected").is_err());
     }
 
+    #[test]
+    fn read_all_test() {
+        let forms = read_all("(a 10) b\n(c (d))").unwrap();
+        assert_eq!(forms, vec![
+            Expression::List(vec![Expression::Symbol("a".into()), Expression::Integer(10)]),
+            Expression::Symbol("b".into()),
+            Expression::List(vec![
+                Expression::Symbol("c".into()),
+                Expression::List(vec![Expression::Symbol("d".into())]),
+            ]),
+        ]);
+        assert_eq!(read_all("  \n ").unwrap(), vec![]);
+        assert!(matches!(read_all("(a) )"), Err(ParseError::UnexpectedClosingParen { .. })));
+        assert!(matches!(read_all("(a) (b"), Err(ParseError::MissingClosingParen { .. })));
+
+        assert_eq!(read_strict("(a b)").unwrap(), read("(a b)").unwrap());
+        assert_eq!(read_strict("(a) b"), Err(ParseError::TrailingInput {
+            span: Span { start: 4, end: 5, line: 1, column: 5 },
+            token: "b".to_string(),
+        }));
+    }
+
+    #[test]
+    fn reader_test() {
+        let src = "(a b) c\n  (d";
+        let mut reader = Reader::new(src);
+        assert_eq!(reader.next(), Some(read("(a b)")));
+        let c = reader.next_spanned().unwrap().unwrap();
+        assert_eq!(c.expr, Expression::Symbol("c".into()));
+        assert_eq!(c.span(), Span { start: 6, end: 7, line: 1, column: 7 });
+        assert!(matches!(reader.next(), Some(Err(ParseError::MissingClosingParen { .. }))));
+        assert_eq!(reader.next(), None);
+
+        assert_eq!(Reader::new("   ").count(), 0);
+        assert_eq!(Reader::new("x \"open").collect::<Vec<_>>(), vec![
+            Ok(Expression::Symbol("x".into())),
+            Err(ParseError::UnterminatedString {
+                span: Span { start: 2, end: 7, line: 1, column: 3 },
+                token: "\"open".to_string(),
+            }),
+        ]);
+    }
+
+    #[test]
+    fn comment_test() {
+        let src = "; header\n(a #| b |# c ; d\n #;(e f) g #;h) #| trailing |#";
+        assert_eq!(read_all(src).unwrap(), vec![Expression::List(vec![
+            Expression::Symbol("a".into()),
+            Expression::Symbol("c".into()),
+            Expression::Symbol("g".into()),
+        ])]);
+        assert_eq!(read("#;(skipped) kept").unwrap(), Expression::Symbol("kept".into()));
+        assert!(matches!(read("(a #;)"), Err(ParseError::UnexpectedClosingParen { .. })));
+        assert!(matches!(read("#| open"), Err(ParseError::UnterminatedComment { .. })));
+
+        let options = ReaderOptions::new().keep_comments(true);
+        let mut reader = Reader::with_options(src, &options);
+        let spanned = reader.next_spanned().unwrap().unwrap();
+        assert!(reader.next().is_none());
+
+        let text = |comments: &[Comment]| -> Vec<(CommentKind, &str)> {
+            comments.iter().map(|c| (c.kind, c.span.source_text(src))).collect()
+        };
+        let spans = &spanned.spans;
+        assert_eq!(text(&spans.leading), vec![(CommentKind::Line, "; header")]);
+        assert_eq!(text(&spans.children[0].leading), vec![]);
+        assert_eq!(text(&spans.children[1].leading), vec![(CommentKind::Block, "#| b |#")]);
+        assert_eq!(text(&spans.children[2].leading), vec![
+            (CommentKind::Line, "; d"),
+            (CommentKind::Datum, "#;(e f)"),
+        ]);
+        assert_eq!(text(&spans.trailing), vec![(CommentKind::Datum, "#;h")]);
+        assert_eq!(text(reader.trailing_comments()), vec![(CommentKind::Block, "#| trailing |#")]);
+
+        // Comments are only recorded when asked for
+        assert_eq!(read_spanned(src).unwrap().spans.leading, vec![]);
+    }
+
+    #[test]
+    fn quote_test() {
+        let list = ReaderOptions::new().quote_style(QuoteStyle::List);
+        let read_list = |src| read_with(src, &list);
+        let quoted = |kind: &'static str, expr| Expression::List(vec![Expression::Symbol(kind.into()), expr]);
+        assert_eq!(read_list("'x").unwrap(), quoted("quote", Expression::Symbol("x".into())));
+        assert_eq!(read_list("`(a ,b ,@c)").unwrap(), quoted("quasiquote", Expression::List(vec![
+            Expression::Symbol("a".into()),
+            quoted("unquote", Expression::Symbol("b".into())),
+            quoted("unquote-splicing", Expression::Symbol("c".into())),
+        ])));
+        assert_eq!(read_list("''x").unwrap(), quoted("quote", quoted("quote", Expression::Symbol("x".into()))));
+        assert!(matches!(read("'"), Err(ParseError::UnexpectedEOF { .. })));
+        assert!(matches!(read_list("(a ')"), Err(ParseError::UnexpectedClosingParen { .. })));
+
+        let expr = Reader::new("`(a ,@b)").next().unwrap().unwrap();
+        assert_eq!(expr, Expression::Quoted(QuoteKind::Quasiquote, Box::new(Expression::List(vec![
+            Expression::Symbol("a".into()),
+            Expression::Quoted(QuoteKind::UnquoteSplicing, Box::new(Expression::Symbol("b".into()))),
+        ]))));
+        assert_eq!(expr.to_string(), "`(a ,@b)");
+
+        let src = "(f 'x)";
+        let spans = read_spanned_with(src, &list).unwrap().spans;
+        assert_eq!(spans.get(&[1]).unwrap().span.source_text(src), "'x");
+        assert_eq!(spans.get(&[1, 0]).unwrap().span.source_text(src), "'");
+        assert_eq!(spans.get(&[1, 1]).unwrap().span.source_text(src), "x");
+        let spans = read_spanned(src).unwrap().spans;
+        assert_eq!(spans.get(&[1, 0]).unwrap().span.source_text(src), "x");
+    }
+
+    #[test]
+    fn error_position_test() {
+        let src = "(a\n  (b c)";
+        assert_eq!(read(src), Err(ParseError::MissingClosingParen {
+            span: Span { start: 10, end: 10, line: 2, column: 8 },
+            open: Span { start: 0, end: 1, line: 1, column: 1 },
+        }));
+        assert_eq!(read(""), Err(ParseError::UnexpectedEOF {
+            span: Span { start: 0, end: 0, line: 1, column: 1 },
+        }));
+        assert_eq!(read("  )"), Err(ParseError::UnexpectedClosingParen {
+            span: Span { start: 2, end: 3, line: 1, column: 3 },
+            found: ')',
+        }));
+        assert_eq!(read("(x \"abc"), Err(ParseError::UnterminatedString {
+            span: Span { start: 3, end: 7, line: 1, column: 4 },
+            token: "\"abc".to_string(),
+        }));
+        assert_eq!(read("(\"a\nb\\xc\")"), Err(ParseError::InvalidEscape {
+            span: Span { start: 5, end: 7, line: 2, column: 2 },
+            sequence: "\\x".to_string(),
+        }));
+    }
+
+    #[test]
+    fn string_literal_test() {
+        let result = read("(msg \"hello (big) world\")").unwrap();
+        assert_eq!(result, Expression::List(vec![
+            Expression::Symbol("msg".into()),
+            Expression::Str(Cow::Borrowed("hello (big) world")),
+        ]));
+
+        let result = read("\"line\\nbreak \\\"quoted\\\" \\u{3bb}\"").unwrap();
+        assert_eq!(result, Expression::Str(Cow::Owned("line\nbreak \"quoted\" \u{3bb}".to_string())));
+
+        assert!(matches!(read("(\"unterminated"), Err(ParseError::UnterminatedString { .. })));
+        assert!(matches!(read("\"bad \\q\""), Err(ParseError::InvalidEscape { .. })));
+    }
+
+    #[test]
+    fn char_literal_test() {
+        let result = read("(#\\a #\\( #\\space #\\x41)").unwrap();
+        assert_eq!(result, Expression::List(vec![
+            Expression::Char('a'),
+            Expression::Char('('),
+            Expression::Char(' '),
+            Expression::Char('A'),
+        ]));
+
+        for src in ["#\\a", "#\\(", "#\\space", "#\\newline", "#\\x3000", "#\\x"] {
+            let expr = read(src).unwrap();
+            assert_eq!(expr.to_string(), src);
+            assert_eq!(expr.to_owned::<StringOwnedSymbol>().to_string(), src);
+        }
+        assert!(matches!(read("#\\nope"), Err(ParseError::InvalidCharacter { .. })));
+    }
+
+    #[test]
+    fn collection_test() {
+        let result = read("{:deps [a b] :tags #{x}}").unwrap();
+        assert_eq!(result, Expression::Map(vec![
+            (Expression::Keyword("deps".into()), Expression::Vector(vec![Expression::Symbol("a".into()), Expression::Symbol("b".into())])),
+            (Expression::Keyword("tags".into()), Expression::Set(vec![Expression::Symbol("x".into())])),
+        ]));
+        assert_eq!(result.to_string(), "{:deps [a b] :tags #{x}}");
+        assert_eq!(result.to_owned::<StringOwnedSymbol>().to_string(), "{:deps [a b] :tags #{x}}");
+        assert_eq!(read("[]").unwrap(), Expression::Vector(vec![]));
+
+        let spanned = read_spanned("{a [1 2]}").unwrap();
+        assert_eq!(spanned.spans.get(&[1, 0]).unwrap().span.start, 4);
+
+        assert!(matches!(read("(a]"), Err(ParseError::MismatchedBracket { expected: ')', found: ']', .. })));
+        assert!(matches!(read("#{a)"), Err(ParseError::MismatchedBracket { expected: '}', found: ')', .. })));
+        assert!(matches!(read("[a"), Err(ParseError::MissingClosingBracket { expected: ']', .. })));
+        assert!(matches!(read("}"), Err(ParseError::UnexpectedClosingParen { found: '}', .. })));
+        assert!(matches!(read("{a}"), Err(ParseError::UnpairedMapKey { .. })));
+        assert!(matches!(read("#[a]"), Err(ParseError::InvalidDisplayHint { .. })));
+        assert!(matches!(read("#[a b c]"), Err(ParseError::InvalidDisplayHint { .. })));
+        assert!(matches!(read("#[a b)"), Err(ParseError::MismatchedBracket { expected: ']', .. })));
+
+        let options = ReaderOptions::new().collections(false);
+        let expr = read_with("([a] {b})", &options).unwrap();
+        assert_eq!(expr, Expression::List(vec![Expression::Symbol("[a]".into()), Expression::Symbol("{b}".into())]));
+    }
+
+    #[test]
+    fn bytevector_test() {
+        let result = read("#u8(0 16 255)").unwrap();
+        assert_eq!(result, Expression::Bytes(vec![0, 16, 255].into()));
+        assert_eq!(result.to_string(), "#u8(0 16 255)");
+        assert_eq!(read("#u8()").unwrap().to_owned::<StringOwnedSymbol>(), OwnedExpression::Bytes(vec![]));
+        assert!(matches!(read("#u8(1 256)"), Err(ParseError::InvalidByte { span, token }) if span.start == 6 && token == "256"));
+        assert!(matches!(read("#u8((1))"), Err(ParseError::InvalidByte { token, .. }) if token == "("));
+        assert!(matches!(read("#u8(1"), Err(ParseError::MissingClosingParen { .. })));
+
+        let options = ReaderOptions::new().bytevectors(false);
+        let expr = Reader::with_options("#u8(1)", &options).next().unwrap().unwrap();
+        assert_eq!(expr, Expression::Symbol("#u8".into()));
+    }
+
+    #[test]
+    fn dotted_list_test() {
+        let result = read("((a . 1) (b c . 2))").unwrap();
+        assert_eq!(result, Expression::List(vec![
+            Expression::DottedList(vec![Expression::Symbol("a".into())], Box::new(Expression::Integer(1))),
+            Expression::DottedList(
+                vec![Expression::Symbol("b".into()), Expression::Symbol("c".into())],
+                Box::new(Expression::Integer(2)),
+            ),
+        ]));
+        assert_eq!(result.to_string(), "((a . 1) (b c . 2))");
+        assert_eq!(read("(a . (b))").unwrap().to_owned::<StringOwnedSymbol>().to_string(), "(a . (b))");
+        assert_eq!(read("[a . b]").unwrap().to_string(), "[a |.| b]");
+
+        let spanned = read_spanned("(a . b)").unwrap();
+        assert_eq!(spanned.spans.get(&[1]).unwrap().span.start, 5);
+
+        let dot_error = |src| match read(src) {
+            Err(ParseError::MisplacedDot { span, token }) if token == "." => span.start,
+            other => panic!("expected a misplaced dot, got {:?}", other),
+        };
+        assert_eq!(dot_error("(. a)"), 1);
+        assert_eq!(dot_error("(a .)"), 3);
+        assert_eq!(dot_error("(a . . b)"), 3);
+        assert_eq!(dot_error("(a . b . c)"), 7);
+        assert!(matches!(read("(a . b c)"), Err(ParseError::ExtraAfterDottedTail { span, token }) if span.start == 7 && token == "c"));
+        assert!(matches!(read("(a . b"), Err(ParseError::MissingClosingParen { .. })));
+
+        let options = ReaderOptions::new().dotted_pairs(false);
+        let expr = Reader::with_options("(a . b)", &options).next().unwrap().unwrap();
+        assert_eq!(expr, Expression::List(vec![Expression::Symbol("a".into()), Expression::Symbol(".".into()), Expression::Symbol("b".into())]));
+    }
+
+    #[test]
+    fn display_round_trip_test() {
+        let sym = |name: &'static str| Expression::Symbol(name.into());
+        let values = vec![
+            Expression::Str("say \"hi\"\\ (x)\n\t\u{7}".into()),
+            Expression::Str("".into()),
+            sym("a b"),
+            sym(""),
+            sym("42"),
+            sym("-1.5"),
+            sym("true"),
+            sym("null"),
+            sym("."),
+            sym(":k"),
+            sym("#t"),
+            sym("#|x"),
+            sym("a|b\\c"),
+            sym("(x)"),
+            sym("line\nbreak"),
+            sym("+"),
+            sym("..."),
+            Expression::Keyword("a b".into()),
+            Expression::Keyword("".into()),
+            Expression::Keyword(":".into()),
+            Expression::Char('('),
+            Expression::Char('|'),
+            Expression::Char('\u{0}'),
+            Expression::Float(1e300),
+            Expression::Float(-0.0),
+            Expression::Float(f64::MIN_POSITIVE),
+            Expression::Integer(i64::MIN),
+            Expression::List(vec![]),
+            Expression::DottedList(vec![sym(".")], Box::new(sym("x y"))),
+            Expression::Map(vec![(Expression::Keyword("k".into()), Expression::Set(vec![sym("}")]))]),
+            Expression::Vector(vec![sym("[a]"), Expression::Null, Expression::Bool(false)]),
+            Expression::Quoted(QuoteKind::Quote, Box::new(sym("x"))),
+            Expression::Quoted(QuoteKind::UnquoteSplicing, Box::new(Expression::List(vec![
+                Expression::Quoted(QuoteKind::Quasiquote, Box::new(sym("@"))),
+                Expression::Quoted(QuoteKind::Unquote, Box::new(sym("y"))),
+            ]))),
+            Expression::List(vec![sym("quote"), sym("x")]),
+            Expression::Quoted(QuoteKind::Unquote, Box::new(sym("@x"))),
+            Expression::Integer(7),
+            Expression::Float(2.5),
+            Expression::Bool(true),
+            Expression::Char('a'),
+            Expression::Bytes(vec![0, 255].into()),
+            Expression::Hinted(Box::new(sym("text/plain")), Box::new(Expression::Str("hi".into()))),
+            Expression::Null,
+            #[cfg(feature = "bignum")]
+            Expression::BigInt(BigInt::from(i64::MAX) * 4),
+            #[cfg(feature = "bignum")]
+            Expression::Ratio(BigRational::new(BigInt::from(-1), BigInt::from(3))),
+        ];
+        for value in values {
+            let printed = value.to_string();
+            assert_eq!(read(&printed).unwrap(), value, "{} did not round trip", printed);
+            let owned = value.to_owned::<StringOwnedSymbol>();
+            assert_eq!(read(&owned.to_string()).unwrap().to_owned(), owned);
+        }
+
+        // With nothing before the dot, a dotted list is just its tail
+        let tail = || Expression::DottedList(vec![sym("a")], Box::new(sym("b")));
+        let headless = Expression::DottedList(vec![], Box::new(tail()));
+        assert_eq!(headless.to_string(), "(a . b)");
+        assert_eq!(read(&headless.to_string()).unwrap(), tail());
+        assert_eq!(read(&headless.to_owned::<StringOwnedSymbol>().to_string()).unwrap(), tail());
+        assert_eq!(headless.pretty(&crate::PrettyOptions::new()), "(a . b)");
+
+        // The identifier fast path agrees with the lexer
+        for name in ["list->vector", "set!", "a1", "x/y", "true", "false", "null", "nil", "e10", "a:b", "inf"] {
+            assert_eq!(is_plain_symbol(name), lexes_as_symbol(name), "{}", name);
+        }
+
+        assert_eq!(sym("a b").to_string(), "|a b|");
+        assert_eq!(sym("a|b").to_string(), "|a\\|b|");
+        assert_eq!(Expression::Keyword("a b".into()).to_string(), ":|a b|");
+        assert_eq!(Expression::Str("\"\\".into()).to_string(), "\"\\\"\\\\\"");
+        assert_eq!(Expression::Quoted(QuoteKind::Unquote, Box::new(sym("@x"))).to_string(), ", @x");
+        assert!(matches!(read("|a b|").unwrap(), Expression::Symbol(Cow::Borrowed("a b"))));
+        assert_eq!(read("|:a|").unwrap(), sym(":a"));
+        assert_eq!(read("|1|").unwrap(), sym("1"));
+        assert!(matches!(read("(|a)"), Err(ParseError::UnterminatedSymbol { token, .. }) if token == "|a)"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn owned_serde_test() {
+        let options = ReaderOptions::new();
+        let owned: OwnedExpression = read_with("(f |a b| '(1 2.5 \"s\" #\\x :k [v] {k v} #{s} (a . b) null true))", &options)
+            .unwrap()
+            .to_owned();
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(serde_json::from_str::<OwnedExpression>(&json).unwrap(), owned);
+
+        let small: OwnedExpression = read_with("(|a b| :k 1)", &options).unwrap().to_owned();
+        assert_eq!(
+            serde_json::to_string(&small).unwrap(),
+            r#"{"List":[{"Symbol":"a b"},{"Keyword":"k"},{"Integer":1}]}"#,
+        );
+
+        #[cfg(feature = "bignum")]
+        {
+            let big: OwnedExpression = read("(1/3 123456789012345678901234567890)").unwrap().to_owned();
+            assert_eq!(serde_json::from_str::<OwnedExpression>(&serde_json::to_string(&big).unwrap()).unwrap(), big);
+        }
+
+        // Custom symbols use the default hooks: `display` out, `from_str` in
+        #[derive(Debug, Clone, PartialEq)]
+        struct Upper(String);
+
+        impl OwnedSymbol for Upper {
+            fn from_str(s: &str) -> Self {
+                Upper(s.to_uppercase())
+            }
+
+            fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        let custom: OwnedExpression<Upper> = read("(define x)").unwrap().to_owned();
+        let json = serde_json::to_string(&custom).unwrap();
+        assert_eq!(json, r#"{"List":[{"Symbol":"DEFINE"},{"Symbol":"X"}]}"#);
+        assert_eq!(serde_json::from_str::<OwnedExpression<Upper>>(&json).unwrap(), custom);
+
+        // The default hooks also round trip names that `display` quotes
+        #[derive(Debug, Clone, PartialEq)]
+        struct Quoting(String);
+
+        impl OwnedSymbol for Quoting {
+            fn from_str(s: &str) -> Self {
+                Quoting(s.to_string())
+            }
+
+            fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_symbol(f, &self.0)
+            }
+        }
+
+        let quoted = OwnedExpression::List(["a b", "1", "", "x|y", "plain"].map(|name| OwnedExpression::Symbol(Quoting::from_str(name))).to_vec());
+        let json = serde_json::to_string(&quoted).unwrap();
+        assert!(json.contains(r#"{"Symbol":"|a b|"}"#));
+        assert_eq!(serde_json::from_str::<OwnedExpression<Quoting>>(&json).unwrap(), quoted);
+    }
+
+    #[test]
+    fn dialect_test() {
+        let read_dialect = |src, dialect| read_with(src, &ReaderOptions::new().dialect(dialect)).unwrap().to_string();
+
+        assert_eq!(read_dialect("(#t #false '() :k [a] #\\a)", Dialect::Scheme), "(true false '() |:k| |[a]| #\\a)");
+        assert_eq!(read_dialect("(if t nil :key #x10 (a . b))", Dialect::CommonLisp), "(if true null :key 16 (a . b))");
+        assert_eq!(read_dialect("`(a, ~b ~@c . 0x1F)", Dialect::Clojure), "`(a ,b ,@c |.| 31)");
+        assert_eq!(read_dialect("(3:a b 12 true \"q\")", Dialect::Rivest), "(\"a b\" |12| |true| \"q\")");
+        assert!(matches!(
+            read_with("(9:abc)", &ReaderOptions::new().dialect(Dialect::Rivest)),
+            Err(ParseError::InvalidVerbatim { .. })
+        ));
+
+        let scheme = ReaderOptions::new().dialect(Dialect::Scheme);
+        assert_eq!(read_with("#t", &scheme).unwrap(), Expression::Bool(true));
+        assert_eq!(read_with("true", &scheme).unwrap(), Expression::Symbol("true".into()));
+        assert_eq!(read_with("[a]", &scheme).unwrap(), Expression::Symbol("[a]".into()));
+        assert_eq!(read_with("#u8(1)", &scheme).unwrap(), Expression::Bytes(vec![1].into()));
+        let lisp = ReaderOptions::new().dialect(Dialect::CommonLisp);
+        assert_eq!(Reader::with_options("#u8(1)", &lisp).next().unwrap().unwrap(), Expression::Symbol("#u8".into()));
+        assert_eq!(read_with("12", &ReaderOptions::new().dialect(Dialect::Rivest)).unwrap(), Expression::Symbol("12".into()));
+
+        // The preset keeps comment and quote handling
+        let options = ReaderOptions::new().quote_style(QuoteStyle::List).dialect(Dialect::Clojure);
+        assert_eq!(read_with("'a", &options).unwrap().to_string(), "(quote a)");
+        assert_eq!(ReaderOptions::new().dialect(Dialect::Sexpression), ReaderOptions::default());
+
+        // Literal words can come from runtime configuration
+        let config = String::from("yes no none");
+        let words: Vec<String> = config.split(' ').map(String::from).collect();
+        let options = ReaderOptions::new().bool_literals([words[0].clone()], [words[1].clone()]).null_literals(words[2..].to_vec());
+        assert_eq!(read_with("(yes no none true)", &options).unwrap().to_string(), "(true false null |true|)");
+
+        let options = ReaderOptions::new().delimiters(",=");
+        assert_eq!(read_with("(a=1, b=\"x=y\" |c=d|)", &options).unwrap().to_string(), "(a 1 b \"x=y\" c=d)");
+    }
+
+    #[test]
+    fn keyword_test() {
+        let plain = ReaderOptions::new().keyword_style(KeywordStyle::Symbol);
+        assert_eq!(read_with(":target", &plain).unwrap(), Expression::Symbol(":target".into()));
+
+        let result = read("(build :target \"x86\" : key:)").unwrap();
+        assert_eq!(result, Expression::List(vec![
+            Expression::Symbol("build".into()),
+            Expression::Keyword("target".into()),
+            Expression::Str(Cow::Borrowed("x86")),
+            Expression::Symbol(":".into()),
+            Expression::Symbol("key:".into()),
+        ]));
+        assert_eq!(result.to_string(), "(build :target \"x86\" : key:)");
+        let owned: OwnedExpression = read(":target").unwrap().to_owned();
+        assert_eq!(owned, OwnedExpression::Keyword(StringOwnedSymbol::from_str("target")));
+        assert_eq!(owned.to_string(), ":target");
+
+        let options = ReaderOptions::new().keyword_style(KeywordStyle::Suffix);
+        let forms: Vec<_> = Reader::with_options(":a b:", &options).map(Result::unwrap).collect();
+        assert_eq!(forms, vec![Expression::Symbol(":a".into()), Expression::Keyword("b".into())]);
+
+        let options = ReaderOptions::new().keyword_style(KeywordStyle::Symbol);
+        let forms: Vec<_> = Reader::with_options(":a b:", &options).map(Result::unwrap).collect();
+        assert_eq!(forms, vec![Expression::Symbol(":a".into()), Expression::Symbol("b:".into())]);
+    }
+
+    #[test]
+    fn read_spanned_test() {
+        let src = "(define (id x)\n  \"λ x\" x)";
+        let spanned = read_spanned(src).unwrap();
+        assert_eq!(spanned.expr, read(src).unwrap());
+        assert_eq!(spanned.span(), Span { start: 0, end: src.len(), line: 1, column: 1 });
+
+        let spans = &spanned.spans;
+        assert_eq!(spans.children.len(), 4);
+        assert_eq!(spans.get(&[1]).unwrap().span.source_text(src), "(id x)");
+        assert_eq!(spans.get(&[1, 1]).unwrap().span, Span { start: 12, end: 13, line: 1, column: 13 });
+        assert_eq!(spans.get(&[2]).unwrap().span, Span { start: 17, end: 23, line: 2, column: 3 });
+        assert_eq!(spans.get(&[3]).unwrap().span, Span { start: 24, end: 25, line: 2, column: 9 });
+        assert!(spans.get(&[1, 2]).is_none());
+
+        let owned: Spanned<OwnedExpression> = spanned.to_owned();
+        assert_eq!(owned.spans, spanned.spans);
+    }
+
     #[test]
     fn fast_path_tests() {
         // Test single character symbols
         let result = read("a").unwrap();
-        assert!(matches!(result, Expression::Symbol("a")));
+        assert!(matches!(result, Expression::Symbol(s) if s == "a"));
         
         // Test number parsing
         let result = read("42").unwrap();
-        assert!(matches!(result, Expression::Number(42.0)));
+        assert!(matches!(result, Expression::Integer(42)));
         
         // Test negative numbers
-        let result = read("-3.14").unwrap();
-        assert!(matches!(result, Expression::Number(-3.14)));
+        let result = read("-2.5").unwrap();
+        assert!(matches!(result, Expression::Float(-2.5)));
+    }
+
+    #[test]
+    fn number_test() {
+        let read_number = |src| read(src).unwrap();
+        assert_eq!(read_number("7"), Expression::Integer(7));
+        assert_eq!(read_number("+7"), Expression::Integer(7));
+        assert_eq!(read_number("-0"), Expression::Integer(0));
+        assert_eq!(read_number("9007199254740993"), Expression::Integer(9007199254740993));
+        assert_eq!(read_number("-9223372036854775808"), Expression::Integer(i64::MIN));
+        assert_eq!(read_number("1.0"), Expression::Float(1.0));
+        assert_eq!(read_number("1."), Expression::Float(1.0));
+        assert_eq!(read_number(".5"), Expression::Float(0.5));
+        assert_eq!(read_number("-6.02e23"), Expression::Float(-6.02e23));
+        assert_eq!(read_number("1E3"), Expression::Float(1000.0));
+        for symbol in ["-", "+", ".", "...", "1e", "1.2.3", "-inf", "nan", "1-2", "e5"] {
+            assert_eq!(read_number(symbol), Expression::Symbol(symbol.into()));
+        }
+
+        #[cfg(not(feature = "bignum"))]
+        assert_eq!(read("(x 9223372036854775808)"), Err(ParseError::IntegerOverflow {
+            span: Span { start: 3, end: 22, line: 1, column: 4 },
+            literal: "9223372036854775808".to_string(),
+        }));
+
+        assert_eq!(Expression::Float(1.0).to_string(), "1.0");
+        assert_eq!(Expression::Integer(1).to_string(), "1");
+        let owned: OwnedExpression = read("(1 1.0)").unwrap().to_owned();
+        assert_eq!(owned.to_string(), "(1 1.0)");
+    }
+
+    #[cfg(feature = "bignum")]
+    #[test]
+    fn bignum_test() {
+        let big = "-123456789012345678901234567890";
+        assert_eq!(read(big).unwrap(), Expression::BigInt(big.parse().unwrap()));
+        assert_eq!(read("9223372036854775807").unwrap(), Expression::Integer(i64::MAX));
+
+        let ratio = |n: i64, d: i64| Expression::Ratio(BigRational::new(n.into(), d.into()));
+        assert_eq!(read("1/3").unwrap(), ratio(1, 3));
+        assert_eq!(read("-6/4").unwrap(), ratio(-3, 2));
+        assert_eq!(read("4/2").unwrap(), Expression::Integer(2));
+        assert!(matches!(read("1/0"), Err(ParseError::InvalidNumber { .. })));
+        for symbol in ["1/", "/2", "1/-2", "1/2/3", "a/b"] {
+            assert_eq!(read(symbol).unwrap(), Expression::Symbol(symbol.into()));
+        }
+
+        let src = format!("({} -3/2 1/3 7)", big);
+        let owned: OwnedExpression = read(&src).unwrap().to_owned();
+        assert_eq!(owned.to_string(), src);
+        assert_eq!(read(&owned.to_string()).unwrap().to_owned::<StringOwnedSymbol>(), owned);
     }
     
     #[test]
@@ -0,0 +1,820 @@
+//! Rivest S-Expression Module
+//!
+//! This module reads and writes the three encodings of Rivest's
+//! S-expression draft. The canonical form is a byte-exact binary encoding
+//! used for hashing and signatures, the advanced form is the readable text
+//! syntax, and the transport form wraps canonical bytes in base64 so they
+//! survive text channels.
+//!
+//! Rivest S-expressions only hold octet strings and lists. Octet strings
+//! that are valid UTF-8 read as [`Expression::Str`] and all others as
+//! [`Expression::Bytes`]; in the advanced form, bare tokens such as `abc`
+//! read as [`Expression::Symbol`]. A display hint, `[text/plain]"hi"`, reads
+//! as an [`Expression::Hinted`] of the hint and the value, which the default
+//! reader writes as `#[text/plain "hi"]`.
+//!
+//! # Features
+//!
+//! - **Canonical form**: `(3:abc[10:text/plain]2:hi)`, with no whitespace and
+//!   one encoding per value, so [`Expression::to_canonical`] output is
+//!   byte-for-byte stable
+//! - **Advanced form**: Tokens, `"quoted"` strings with C-style escapes,
+//!   `#616263#` hex, `|YWJj|` base64 and `3:abc` verbatim strings, each with
+//!   an optional length prefix
+//! - **Transport form**: `{KDM6YWJjKQ==}` anywhere a value may appear
+//! - **Zero-copy**: Verbatim strings, tokens and quoted strings without
+//!   escapes borrow from the input
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::{read_advanced, read_canonical, Expression};
+//!
+//! let expr = read_advanced(b"(public-key (rsa (e #010001#) (n |AQAB|)))").unwrap();
+//! let canonical = expr.to_canonical().unwrap();
+//! assert_eq!(canonical, b"(10:public-key(3:rsa(1:e3:\x01\x00\x01)(1:n3:\x01\x00\x01)))");
+//! assert_eq!(read_canonical(&canonical).unwrap().to_canonical().unwrap(), canonical);
+//!
+//! let transport = Expression::Str("abc".into()).to_transport().unwrap();
+//! assert_eq!(transport, "{MzphYmM=}");
+//! assert_eq!(read_advanced(transport.as_bytes()).unwrap(), Expression::Str("abc".into()));
+//! ```
+
+use std::borrow::Cow;
+
+use crate::error::{EncodeError, ParseError};
+use crate::reader::{Expression, OwnedExpression, OwnedSymbol};
+use crate::span::Span;
+
+/// The deepest nesting of lists the readers accept, so untrusted input
+/// cannot overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Read one expression in canonical form.
+///
+/// The input must be exactly one canonical expression: no whitespace, only
+/// `len:bytes` verbatim strings, and no leading zeros in lengths.
+///
+/// # Arguments
+///
+/// * `src` - The canonical bytes
+///
+/// # Returns
+///
+/// The expression, borrowing every octet string from `src`
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] whose span counts columns in bytes, including
+/// [`ParseError::InvalidEncoding`] for lists nested more than 256 deep. Such
+/// an error can only be rendered against `src` if `src` is valid UTF-8.
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read_canonical, Expression};
+///
+/// let expr = read_canonical(b"(3:abc2:\xff\x00)").unwrap();
+/// assert_eq!(expr, Expression::List(vec![
+///     Expression::Str("abc".into()),
+///     Expression::Bytes(vec![0xff, 0x00].into()),
+/// ]));
+/// assert!(read_canonical(b"(3:abc 1:d)").is_err());
+/// ```
+pub fn read_canonical(src: &[u8]) -> Result<Expression<'_>, ParseError> {
+    Decoder { src, pos: 0, advanced: false, depth: 0 }.read()
+}
+
+/// Read one expression in advanced or transport form.
+///
+/// Whitespace may separate elements, and every octet string may be written
+/// as a token, quoted string, hex string, base64 string or verbatim string.
+/// A `{...}` transport block decodes to the canonical expression inside it.
+///
+/// # Arguments
+///
+/// * `src` - The advanced form text
+///
+/// # Returns
+///
+/// The expression; tokens read as symbols and other octet strings as strings
+/// or bytes
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] whose span counts columns in bytes. Errors inside
+/// a transport block are reported as [`ParseError::InvalidEncoding`] for the
+/// whole block, as are lists nested more than 256 deep.
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{read_advanced, Expression};
+///
+/// let expr = read_advanced(b"(name [text/plain] \"Alice\" 3:\xff\x00\x01)").unwrap();
+/// assert_eq!(expr, Expression::List(vec![
+///     Expression::Symbol("name".into()),
+///     Expression::Hinted(Box::new(Expression::Symbol("text/plain".into())), Box::new(Expression::Str("Alice".into()))),
+///     Expression::Bytes(vec![0xff, 0, 1].into()),
+/// ]));
+/// ```
+pub fn read_advanced(src: &[u8]) -> Result<Expression<'_>, ParseError> {
+    Decoder { src, pos: 0, advanced: true, depth: 0 }.read()
+}
+
+impl<'a> Expression<'a> {
+    /// Write this expression in canonical form.
+    ///
+    /// Symbols, strings and bytes are written as verbatim strings, integers
+    /// as their decimal digits, and [`Expression::Hinted`] values as display
+    /// hints.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot
+    /// hold, such as floats, keywords, vectors or maps
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::read;
+    ///
+    /// let expr = read("(add \"x y\" 12 #[hint v])").unwrap();
+    /// assert_eq!(expr.to_canonical().unwrap(), b"(3:add3:x y2:12[4:hint]1:v)");
+    /// assert!(read("(1.5)").unwrap().to_canonical().is_err());
+    /// assert!(read("[a b]").unwrap().to_canonical().is_err());
+    /// ```
+    pub fn to_canonical(&self) -> Result<Vec<u8>, EncodeError> {
+        let mut out = Vec::new();
+        write_canonical(self, &mut out, "canonical")?;
+        Ok(out)
+    }
+
+    /// Write this expression in advanced form.
+    ///
+    /// Symbols that are valid tokens are written bare and all other text is
+    /// quoted. Bytes that are not UTF-8 are written in base64.
+    ///
+    /// [`read_advanced`] gives back an expression with the same canonical
+    /// form, not necessarily the same expression: a symbol that is not a
+    /// valid token, such as `|a b|`, reads back as a string, and so does an
+    /// integer, since Rivest S-expressions only hold octet strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot hold
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::read;
+    ///
+    /// let expr = read("(greet \"hello world\" #u8(255))").unwrap();
+    /// assert_eq!(expr.to_advanced().unwrap(), "(greet \"hello world\" |/w==|)");
+    /// ```
+    pub fn to_advanced(&self) -> Result<String, EncodeError> {
+        let mut out = String::new();
+        write_advanced(self, &mut out)?;
+        Ok(out)
+    }
+
+    /// Write this expression in transport form: its canonical form in
+    /// base64, between braces.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot hold
+    pub fn to_transport(&self) -> Result<String, EncodeError> {
+        let mut canonical = Vec::new();
+        write_canonical(self, &mut canonical, "transport")?;
+        Ok(format!("{{{}}}", base64_encode(&canonical)))
+    }
+}
+
+impl<S: OwnedSymbol + AsRef<str>> OwnedExpression<S> {
+    /// Write this expression in canonical form.
+    ///
+    /// See [`Expression::to_canonical`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot hold
+    pub fn to_canonical(&self) -> Result<Vec<u8>, EncodeError> {
+        self.borrowed().to_canonical()
+    }
+
+    /// Write this expression in advanced form.
+    ///
+    /// See [`Expression::to_advanced`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot hold
+    pub fn to_advanced(&self) -> Result<String, EncodeError> {
+        self.borrowed().to_advanced()
+    }
+
+    /// Write this expression in transport form.
+    ///
+    /// See [`Expression::to_transport`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] for values Rivest S-expressions cannot hold
+    pub fn to_transport(&self) -> Result<String, EncodeError> {
+        self.borrowed().to_transport()
+    }
+}
+
+/// The octets of an expression that can be written as an octet string.
+fn atom_octets<'e>(expr: &'e Expression<'_>) -> Option<Cow<'e, [u8]>> {
+    match expr {
+        Expression::Symbol(s) | Expression::Str(s) => Some(Cow::Borrowed(s.as_bytes())),
+        Expression::Bytes(bytes) => Some(Cow::Borrowed(bytes)),
+        Expression::Integer(n) => Some(Cow::Owned(n.to_string().into_bytes())),
+        #[cfg(feature = "bignum")]
+        Expression::BigInt(n) => Some(Cow::Owned(n.to_string().into_bytes())),
+        _ => None,
+    }
+}
+
+/// The hint and value of a display hint whose parts are both octet strings.
+fn display_hint<'e, 'a>(expr: &'e Expression<'a>) -> Option<(&'e Expression<'a>, &'e Expression<'a>)> {
+    match expr {
+        Expression::Hinted(hint, value) if atom_octets(hint).is_some() && atom_octets(value).is_some() => {
+            Some((hint, value))
+        }
+        _ => None,
+    }
+}
+
+/// Describe an expression that cannot be written, for an [`EncodeError`].
+fn kind_name(expr: &Expression<'_>) -> &'static str {
+    match expr {
+        Expression::Float(_) => "float",
+        #[cfg(feature = "bignum")]
+        Expression::Ratio(_) => "ratio",
+        Expression::Bool(_) => "boolean",
+        Expression::Char(_) => "character",
+        Expression::Keyword(_) => "keyword",
+        Expression::DottedList(..) => "dotted list",
+        Expression::Vector(_) => "vector",
+        Expression::Map(_) => "map",
+        Expression::Set(_) => "set",
+        Expression::Hinted(..) => "display hint",
+        Expression::Quoted(..) => "quoted expression",
+        Expression::Null => "null",
+        _ => "value",
+    }
+}
+
+/// Append the canonical form of `expr`, naming `encoding` in errors.
+fn write_canonical(expr: &Expression<'_>, out: &mut Vec<u8>, encoding: &'static str) -> Result<(), EncodeError> {
+    if let Expression::List(items) = expr {
+        out.push(b'(');
+        for item in items {
+            write_canonical(item, out, encoding)?;
+        }
+        out.push(b')');
+    } else if let Some((hint, value)) = display_hint(expr) {
+        out.push(b'[');
+        write_canonical(hint, out, encoding)?;
+        out.push(b']');
+        write_canonical(value, out, encoding)?;
+    } else if let Some(octets) = atom_octets(expr) {
+        out.extend_from_slice(octets.len().to_string().as_bytes());
+        out.push(b':');
+        out.extend_from_slice(&octets);
+    } else {
+        return Err(EncodeError { kind: kind_name(expr), encoding });
+    }
+    Ok(())
+}
+
+/// Append the advanced form of `expr`.
+fn write_advanced(expr: &Expression<'_>, out: &mut String) -> Result<(), EncodeError> {
+    if let Expression::List(items) = expr {
+        out.push('(');
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            write_advanced(item, out)?;
+        }
+        out.push(')');
+        return Ok(());
+    }
+    if let Some((hint, value)) = display_hint(expr) {
+        out.push('[');
+        write_advanced(hint, out)?;
+        out.push(']');
+        return write_advanced(value, out);
+    }
+    match expr {
+        Expression::Symbol(name) if is_token(name.as_bytes()) => out.push_str(name),
+        _ => match atom_octets(expr) {
+            Some(octets) => match std::str::from_utf8(&octets) {
+                Ok(text) => write_quoted(out, text),
+                Err(_) => {
+                    out.push('|');
+                    out.push_str(&base64_encode(&octets));
+                    out.push('|');
+                }
+            },
+            None => return Err(EncodeError { kind: kind_name(expr), encoding: "advanced" }),
+        },
+    }
+    Ok(())
+}
+
+/// Append `text` as a quoted string with Rivest's C-style escapes.
+fn write_quoted(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{b}' => out.push_str("\\v"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c.is_ascii_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Returns `true` if `bytes` can be written as a bare advanced form token.
+fn is_token(bytes: &[u8]) -> bool {
+    match bytes.split_first() {
+        Some((first, rest)) => is_token_char(*first) && !first.is_ascii_digit() && rest.iter().all(|&b| is_token_char(b)),
+        None => false,
+    }
+}
+
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'/' | b'_' | b':' | b'*' | b'+' | b'=')
+}
+
+/// A recursive descent reader over canonical or advanced form bytes.
+struct Decoder<'a> {
+    src: &'a [u8],
+    pos: usize,
+    /// Accept the advanced form, not only the canonical form
+    advanced: bool,
+    /// The number of lists being read
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Read exactly one expression, allowing surrounding whitespace in the
+    /// advanced form.
+    fn read(mut self) -> Result<Expression<'a>, ParseError> {
+        let expr = self.value()?;
+        self.skip_whitespace();
+        if self.pos < self.src.len() {
+            let token = String::from_utf8_lossy(&self.src[self.pos..self.pos + 1]).into_owned();
+            return Err(ParseError::TrailingInput { span: self.span(self.pos, self.pos + 1), token });
+        }
+        Ok(expr)
+    }
+
+    /// The span of `src[start..end]`, with columns counted in bytes.
+    fn span(&self, start: usize, end: usize) -> Span {
+        let before = &self.src[..start];
+        let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        Span {
+            start,
+            end,
+            line: before.iter().filter(|&&b| b == b'\n').count() + 1,
+            column: start - line_start + 1,
+        }
+    }
+
+    /// An [`ParseError::InvalidEncoding`] for `src[start..end]`.
+    fn invalid(&self, start: usize, end: usize, what: &'static str) -> ParseError {
+        ParseError::InvalidEncoding { span: self.span(start, end.min(self.src.len())), what }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        if self.advanced {
+            while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    /// Read a list, display hint, transport block or octet string.
+    fn value(&mut self) -> Result<Expression<'a>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some(b'(') => self.list(),
+            Some(b')') => Err(ParseError::UnexpectedClosingParen { span: self.span(start, start + 1), found: ')' }),
+            Some(b'[') => {
+                self.pos += 1;
+                let hint = self.simple_string()?;
+                self.skip_whitespace();
+                if self.peek() != Some(b']') {
+                    return Err(self.invalid(start, self.pos + 1, "display hint"));
+                }
+                self.pos += 1;
+                let value = self.simple_string()?;
+                Ok(Expression::Hinted(Box::new(hint), Box::new(value)))
+            }
+            Some(b'{') if self.advanced => self.transport(),
+            _ => self.simple_string(),
+        }
+    }
+
+    fn list(&mut self) -> Result<Expression<'a>, ParseError> {
+        let open = self.span(self.pos, self.pos + 1);
+        if self.depth == MAX_DEPTH {
+            return Err(self.invalid(self.pos, self.pos + 1, "nesting depth"));
+        }
+        self.depth += 1;
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b')') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(Expression::List(items));
+                }
+                Some(_) => items.push(self.value()?),
+                None => return Err(ParseError::MissingClosingParen { span: self.span(self.pos, self.pos), open }),
+            }
+        }
+    }
+
+    /// Read an octet string in any form the decoder accepts.
+    fn simple_string(&mut self) -> Result<Expression<'a>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let length = self.length_prefix()?;
+        match (self.peek(), length) {
+            (Some(b':'), Some(length)) => {
+                self.pos += 1;
+                if self.src.len() - self.pos < length {
+                    return Err(ParseError::InvalidVerbatim { span: self.span(start, self.src.len()) });
+                }
+                let octets = &self.src[self.pos..self.pos + length];
+                self.pos += length;
+                Ok(from_octets(Cow::Borrowed(octets)))
+            }
+            (Some(b'"'), _) if self.advanced => {
+                let octets = self.quoted()?;
+                self.check_length(start, length, octets)
+            }
+            (Some(b'#'), _) if self.advanced => {
+                let octets = self.delimited(b'#', "hex string", hex_decode)?;
+                self.check_length(start, length, Cow::Owned(octets))
+            }
+            (Some(b'|'), _) if self.advanced => {
+                let octets = self.delimited(b'|', "base64 string", base64_decode)?;
+                self.check_length(start, length, Cow::Owned(octets))
+            }
+            (Some(b), None) if self.advanced && is_token_char(b) && !b.is_ascii_digit() => {
+                while self.peek().is_some_and(is_token_char) {
+                    self.pos += 1;
+                }
+                let token = std::str::from_utf8(&self.src[start..self.pos]).expect("token characters are ASCII");
+                Ok(Expression::Symbol(Cow::Borrowed(token)))
+            }
+            (None, _) => Err(ParseError::UnexpectedEOF { span: self.span(self.pos, self.pos) }),
+            (Some(_), Some(_)) => Err(self.invalid(start, self.pos + 1, "length prefix")),
+            (Some(_), None) => Err(self.invalid(start, start + 1, "character")),
+        }
+    }
+
+    /// Read an optional decimal length, rejecting leading zeros.
+    fn length_prefix(&mut self) -> Result<Option<usize>, ParseError> {
+        let start = self.pos;
+        let mut length: usize = 0;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            if self.pos > start && length == 0 {
+                return Err(self.invalid(start, self.pos + 1, "length prefix"));
+            }
+            length = length
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(usize::from(digit - b'0')))
+                .ok_or_else(|| self.invalid(start, self.pos + 1, "length prefix"))?;
+            self.pos += 1;
+        }
+        Ok((self.pos > start).then_some(length))
+    }
+
+    /// Check decoded octets against their length prefix, if one was given.
+    fn check_length(&self, start: usize, length: Option<usize>, octets: Cow<'a, [u8]>) -> Result<Expression<'a>, ParseError> {
+        match length {
+            Some(length) if length != octets.len() => Err(self.invalid(start, self.pos, "length prefix")),
+            _ => Ok(from_octets(octets)),
+        }
+    }
+
+    /// Read a quoted string, borrowing it unless it has escapes.
+    fn quoted(&mut self) -> Result<Cow<'a, [u8]>, ParseError> {
+        let start = self.pos;
+        let body = start + 1;
+        let mut end = body;
+        let mut escaped = false;
+        loop {
+            match self.src.get(end) {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    escaped = true;
+                    end += 2;
+                }
+                Some(_) => end += 1,
+                None => {
+                    let token = String::from_utf8_lossy(&self.src[start..]).into_owned();
+                    return Err(ParseError::UnterminatedString { span: self.span(start, self.src.len()), token });
+                }
+            }
+        }
+        self.pos = end + 1;
+        if !escaped {
+            return Ok(Cow::Borrowed(&self.src[body..end]));
+        }
+
+        let mut out = Vec::with_capacity(end - body);
+        let mut i = body;
+        while i < end {
+            if self.src[i] != b'\\' {
+                out.push(self.src[i]);
+                i += 1;
+                continue;
+            }
+            let next = self.src[i + 1];
+            let len = match next {
+                b'x' | b'0'..=b'7' => 4,
+                b'\n' if self.src.get(i + 2) == Some(&b'\r') => 3,
+                b'\r' if self.src.get(i + 2) == Some(&b'\n') => 3,
+                _ => 2,
+            };
+            // `Some(None)` is a backslash before a line break, which
+            // continues the string without adding anything
+            let byte = match next {
+                b'b' => Some(Some(0x08)),
+                b't' => Some(Some(b'\t')),
+                b'v' => Some(Some(0x0b)),
+                b'n' => Some(Some(b'\n')),
+                b'f' => Some(Some(0x0c)),
+                b'r' => Some(Some(b'\r')),
+                b'"' | b'\'' | b'\\' => Some(Some(next)),
+                b'\n' | b'\r' => Some(None),
+                b'x' => self.src.get(i + 2..i + 4).and_then(|hex| digits_value(hex, 16)).map(Some),
+                b'0'..=b'7' => self.src.get(i + 1..i + 4).and_then(|oct| digits_value(oct, 8)).map(Some),
+                _ => None,
+            };
+            match byte {
+                Some(Some(byte)) => out.push(byte),
+                Some(None) => {}
+                None => {
+                    let stop = (i + len).min(end);
+                    return Err(ParseError::InvalidEscape {
+                        span: self.span(i, stop),
+                        sequence: String::from_utf8_lossy(&self.src[i..stop]).into_owned(),
+                    });
+                }
+            }
+            i += len;
+        }
+        Ok(Cow::Owned(out))
+    }
+
+    /// Read a `#hex#` or `|base64|` string with the given decoder.
+    fn delimited(&mut self, delimiter: u8, what: &'static str, decode: fn(&[u8]) -> Option<Vec<u8>>) -> Result<Vec<u8>, ParseError> {
+        let start = self.pos;
+        let body = start + 1;
+        let end = match self.src[body..].iter().position(|&b| b == delimiter) {
+            Some(len) => body + len,
+            None => return Err(self.invalid(start, self.src.len(), what)),
+        };
+        self.pos = end + 1;
+        decode(&self.src[body..end]).ok_or_else(|| self.invalid(start, self.pos, what))
+    }
+
+    /// Read a `{base64}` transport block holding a canonical expression.
+    fn transport(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.pos;
+        let bytes = self.delimited(b'}', "transport data", base64_decode)?;
+        match read_canonical(&bytes) {
+            Ok(expr) => Ok(detach(expr)),
+            Err(_) => Err(self.invalid(start, self.pos, "transport data")),
+        }
+    }
+}
+
+/// An octet string as a string if it is UTF-8, or as bytes otherwise.
+fn from_octets(octets: Cow<'_, [u8]>) -> Expression<'_> {
+    match octets {
+        Cow::Borrowed(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => Expression::Str(Cow::Borrowed(text)),
+            Err(_) => Expression::Bytes(Cow::Borrowed(bytes)),
+        },
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => Expression::Str(Cow::Owned(text)),
+            Err(err) => Expression::Bytes(Cow::Owned(err.into_bytes())),
+        },
+    }
+}
+
+/// Copy a canonical expression out of the buffer it was decoded from.
+fn detach(expr: Expression<'_>) -> Expression<'static> {
+    match expr {
+        Expression::Str(s) => Expression::Str(Cow::Owned(s.into_owned())),
+        Expression::Bytes(bytes) => Expression::Bytes(Cow::Owned(bytes.into_owned())),
+        Expression::List(items) => Expression::List(items.into_iter().map(detach).collect()),
+        Expression::Hinted(hint, value) => Expression::Hinted(Box::new(detach(*hint)), Box::new(detach(*value))),
+        _ => unreachable!("canonical input only holds strings, bytes, lists and display hints"),
+    }
+}
+
+/// The value of a fixed number of digits in `radix`, if it fits in a byte.
+fn digits_value(digits: &[u8], radix: u32) -> Option<u8> {
+    let text = std::str::from_utf8(digits).ok()?;
+    if !text.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    u8::from_str_radix(text, radix).ok()
+}
+
+/// Decode hex digits, ignoring whitespace between them.
+fn hex_decode(src: &[u8]) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = src.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    digits.chunks(2).map(|pair| digits_value(pair, 16)).collect()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded standard base64.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode standard base64, ignoring whitespace; padding is optional.
+fn base64_decode(src: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(src.len() / 4 * 3);
+    let (mut bits, mut count) = (0u32, 0);
+    let mut padding = false;
+    for &b in src.iter().filter(|b| !b.is_ascii_whitespace()) {
+        if b == b'=' {
+            padding = true;
+            continue;
+        }
+        if padding {
+            return None;
+        }
+        let value = BASE64.iter().position(|&c| c == b)?;
+        bits = (bits << 6) | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+            bits &= (1 << count) - 1;
+        }
+    }
+    // A lone trailing sextet cannot hold a whole byte
+    (count < 6).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{read, StringOwnedSymbol};
+
+    fn str(s: &str) -> Expression<'_> {
+        Expression::Str(s.into())
+    }
+
+    fn hinted<'a>(hint: Expression<'a>, value: Expression<'a>) -> Expression<'a> {
+        Expression::Hinted(Box::new(hint), Box::new(value))
+    }
+
+    #[test]
+    fn canonical_test() {
+        let src: &[u8] = b"(4:cert(7:subject[10:text/plain]5:Alice)(3:key3:\x00\xff\x10)0:)";
+        let expr = read_canonical(src).unwrap();
+        assert_eq!(expr, Expression::List(vec![
+            str("cert"),
+            Expression::List(vec![str("subject"), hinted(str("text/plain"), str("Alice"))]),
+            Expression::List(vec![str("key"), Expression::Bytes(vec![0x00, 0xff, 0x10].into())]),
+            str(""),
+        ]));
+        assert!(matches!(expr, Expression::List(ref items) if matches!(items[3], Expression::Str(Cow::Borrowed(_)))));
+        assert_eq!(expr.to_canonical().unwrap(), src);
+        assert_eq!(read_advanced(expr.to_advanced().unwrap().as_bytes()).unwrap(), expr);
+        assert_eq!(read_advanced(expr.to_transport().unwrap().as_bytes()).unwrap(), expr);
+    }
+
+    #[test]
+    fn advanced_test() {
+        let src = b"  (data  abc \"a\\x41\\101\\n\\\n\" #61 62# |YWJj| 3|YWJj| 3:x y [hint] z\n  {MzphYmM=} \"\") ";
+        assert_eq!(read_advanced(src).unwrap(), Expression::List(vec![
+            Expression::Symbol("data".into()),
+            Expression::Symbol("abc".into()),
+            str("aAA\n"),
+            str("ab"),
+            str("abc"),
+            str("abc"),
+            str("x y"),
+            hinted(Expression::Symbol("hint".into()), Expression::Symbol("z".into())),
+            str("abc"),
+            str(""),
+        ]));
+
+        let expr = read("(sig \"two words\" \"tab\\t\" 42 #u8(0 159) .dot)").unwrap();
+        let advanced = expr.to_advanced().unwrap();
+        assert_eq!(advanced, "(sig \"two words\" \"tab\\t\" \"42\" |AJ8=| .dot)");
+        assert_eq!(read_advanced(advanced.as_bytes()).unwrap().to_canonical(), expr.to_canonical());
+
+        let expr = Expression::List(vec![Expression::Symbol("a b".into()), Expression::Integer(42), Expression::Symbol("c".into())]);
+        let advanced = expr.to_advanced().unwrap();
+        let back = read_advanced(advanced.as_bytes()).unwrap();
+        assert_eq!(back, Expression::List(vec![str("a b"), str("42"), Expression::Symbol("c".into())]));
+        assert_eq!(back.to_canonical(), expr.to_canonical());
+    }
+
+    #[test]
+    fn owned_test() {
+        let owned = read("(a \"b c\" #[hint v])").unwrap().to_owned::<StringOwnedSymbol>();
+        assert_eq!(owned.to_canonical().unwrap(), b"(1:a3:b c[4:hint]1:v)");
+        assert_eq!(owned.to_advanced().unwrap(), "(a \"b c\" [hint]v)");
+        assert_eq!(owned.to_transport().unwrap(), "{KDE6YTM6YiBjWzQ6aGludF0xOnYp}");
+    }
+
+    #[test]
+    fn error_test() {
+        assert!(matches!(read_canonical(b""), Err(ParseError::UnexpectedEOF { .. })));
+        assert!(matches!(read_canonical(b"(1:a"), Err(ParseError::MissingClosingParen { .. })));
+        assert!(matches!(read_canonical(b"1:a)"), Err(ParseError::TrailingInput { token, .. }) if token == ")"));
+        assert!(matches!(read_canonical(b")"), Err(ParseError::UnexpectedClosingParen { found: ')', .. })));
+        assert!(matches!(read_canonical(b"5:abc"), Err(ParseError::InvalidVerbatim { .. })));
+        assert!(matches!(read_canonical(b"01:a"), Err(ParseError::InvalidEncoding { what: "length prefix", .. })));
+        assert!(matches!(read_canonical(b"abc"), Err(ParseError::InvalidEncoding { what: "character", .. })));
+        assert!(matches!(read_advanced(b"4\"abc\""), Err(ParseError::InvalidEncoding { what: "length prefix", .. })));
+        assert!(matches!(read_advanced(b"#abc#"), Err(ParseError::InvalidEncoding { what: "hex string", .. })));
+        assert!(matches!(read_advanced(b"|a*|"), Err(ParseError::InvalidEncoding { what: "base64 string", .. })));
+        assert!(matches!(read_advanced(b"{YWJj}"), Err(ParseError::InvalidEncoding { what: "transport data", .. })));
+        assert!(matches!(read_advanced(b"[a b]c"), Err(ParseError::InvalidEncoding { what: "display hint", .. })));
+        assert!(matches!(read_advanced(b"\"a\\q\""), Err(ParseError::InvalidEscape { .. })));
+        assert!(matches!(read_advanced(b"\"abc"), Err(ParseError::UnterminatedString { token, .. }) if token == "\"abc"));
+        let nested = |depth: usize| [b"(".repeat(depth), b")".repeat(depth)].concat();
+        assert!(read_canonical(&nested(MAX_DEPTH)).is_ok());
+        assert!(read_advanced(&nested(MAX_DEPTH)).is_ok());
+        for src in [nested(MAX_DEPTH + 1), b"(".repeat(2_000_000)] {
+            let err = read_canonical(&src).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidEncoding { what: "nesting depth", .. }));
+            assert_eq!(err.span().start, MAX_DEPTH);
+            assert!(matches!(read_advanced(&src), Err(ParseError::InvalidEncoding { what: "nesting depth", .. })));
+        }
+        assert_eq!(read_advanced(b"(a\n  b ))").unwrap_err().span(), Span { start: 8, end: 9, line: 2, column: 6 });
+
+        let err = read("(a 1.5)").unwrap().to_canonical().unwrap_err();
+        assert_eq!(err, EncodeError { kind: "float", encoding: "canonical" });
+        assert_eq!(err.to_string(), "float cannot be written in canonical form");
+        assert_eq!(read(":k").unwrap().to_advanced().unwrap_err().kind, "keyword");
+        assert_eq!(read("[a b c]").unwrap().to_transport().unwrap_err().encoding, "transport");
+        assert_eq!(read("[a b]").unwrap().to_canonical().unwrap_err(), EncodeError { kind: "vector", encoding: "canonical" });
+        assert_eq!(read("[a b]").unwrap().to_advanced().unwrap_err().kind, "vector");
+        assert_eq!(read("#[(a) b]").unwrap().to_canonical().unwrap_err().kind, "display hint");
+    }
+
+    #[test]
+    fn base64_test() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = base64_encode(input);
+            assert_eq!(base64_decode(encoded.as_bytes()).unwrap(), input);
+            assert_eq!(base64_decode(encoded.trim_end_matches('=').as_bytes()).unwrap(), input);
+        }
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_decode(b"Zm9v\n YmFy").unwrap(), b"foobar");
+        assert_eq!(base64_decode(b"Z"), None);
+        assert_eq!(base64_decode(b"Zm=9"), None);
+    }
+}
@@ -0,0 +1,479 @@
+//! Serde Serializer Module
+//!
+//! This module turns any [`Serialize`] value into an [`OwnedExpression`], and
+//! from there into S-expression text using the round-trip safe printer or the
+//! pretty printer. It is available with the `serde` feature.
+//!
+//! # Features
+//!
+//! - **Structs**: Written as keyword-argument lists, `(:name "web" :port 80)`
+//! - **Enums**: Unit variants are symbols, other variants are tagged lists
+//!   such as `(Circle 1.5)` or `(Rect :w 2 :h 3)`
+//! - **Options**: `None` and `()` are `null`; `Some(x)` is just `x`
+//! - **Maps**: Written as `{k v}` map literals, with keys of any type
+//! - **Sequences**: Vectors, tuples and tuple structs are lists
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Server {
+//!     name: String,
+//!     port: u16,
+//!     tags: Vec<&'static str>,
+//!     backup: Option<String>,
+//! }
+//!
+//! let server = Server { name: "web".into(), port: 80, tags: vec!["a", "b"], backup: None };
+//! assert_eq!(
+//!     sexpression::to_string(&server).unwrap(),
+//!     "(:name \"web\" :port 80 :tags (\"a\" \"b\") :backup null)",
+//! );
+//! ```
+
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+use serde::ser::{self, Serialize};
+
+use crate::error::SerdeError;
+use crate::pretty::PrettyOptions;
+use crate::reader::{OwnedExpression, OwnedSymbol, StringOwnedSymbol};
+
+/// Convert a value into an owned S-expression.
+///
+/// # Arguments
+///
+/// * `value` - Any value implementing [`Serialize`]
+///
+/// # Returns
+///
+/// The expression tree for the value
+///
+/// # Errors
+///
+/// Returns [`SerdeError::Message`] if the value's `Serialize` implementation
+/// fails, or for integers outside the range of `i64` without the `bignum`
+/// feature
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::{to_expression, OwnedExpression};
+///
+/// assert_eq!(to_expression(&(1, true)).unwrap(), OwnedExpression::List(vec![
+///     OwnedExpression::Integer(1),
+///     OwnedExpression::Bool(true),
+/// ]));
+/// ```
+pub fn to_expression<T: ?Sized + Serialize>(value: &T) -> Result<OwnedExpression, SerdeError> {
+    value.serialize(Serializer)
+}
+
+/// Convert a value into S-expression text on a single line.
+///
+/// The text reads back as the same expression, so it can be passed to
+/// [`from_str`](crate::from_str).
+///
+/// # Errors
+///
+/// See [`to_expression`]
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// let map = BTreeMap::from([("a b", 1), ("c", 2)]);
+/// assert_eq!(sexpression::to_string(&map).unwrap(), "{\"a b\" 1 \"c\" 2}");
+/// ```
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, SerdeError> {
+    Ok(to_expression(value)?.to_string())
+}
+
+/// Convert a value into S-expression text laid out by the pretty printer.
+///
+/// # Errors
+///
+/// See [`to_expression`]
+///
+/// # Examples
+///
+/// ```rust
+/// use sexpression::PrettyOptions;
+///
+/// let text = sexpression::to_string_pretty(&vec![[1, 2], [3, 4]], &PrettyOptions::new().width(8)).unwrap();
+/// assert_eq!(text, "((1 2)\n (3 4))");
+/// ```
+pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T, options: &PrettyOptions) -> Result<String, SerdeError> {
+    Ok(to_expression(value)?.pretty(options))
+}
+
+/// A serde [`Serializer`](ser::Serializer) that builds an [`OwnedExpression`].
+///
+/// Most code should call [`to_expression`] or [`to_string`] instead of using
+/// this type directly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer;
+
+fn symbol(name: &str) -> OwnedExpression {
+    OwnedExpression::Symbol(StringOwnedSymbol::from_str(name))
+}
+
+/// Convert an integer that may not fit in an `i64`.
+#[cfg(feature = "bignum")]
+fn wide_integer<T: Copy + TryInto<i64>>(value: T) -> Result<OwnedExpression, SerdeError>
+where
+    BigInt: From<T>,
+{
+    Ok(match value.try_into() {
+        Ok(n) => OwnedExpression::Integer(n),
+        Err(_) => OwnedExpression::BigInt(BigInt::from(value)),
+    })
+}
+
+/// Convert an integer that may not fit in an `i64`.
+#[cfg(not(feature = "bignum"))]
+fn wide_integer<T: Copy + std::fmt::Display + TryInto<i64>>(value: T) -> Result<OwnedExpression, SerdeError> {
+    value
+        .try_into()
+        .map(OwnedExpression::Integer)
+        .map_err(|_| SerdeError::Message(format!("integer {} does not fit in an i64 without the bignum feature", value)))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeList;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeList;
+    type SerializeStructVariant = SerializeList;
+
+    fn serialize_bool(self, v: bool) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<OwnedExpression, SerdeError> {
+        wide_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Integer(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<OwnedExpression, SerdeError> {
+        wide_integer(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<OwnedExpression, SerdeError> {
+        wide_integer(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Float(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Str(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<OwnedExpression, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<OwnedExpression, SerdeError> {
+        Ok(symbol(variant))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<OwnedExpression, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(vec![symbol(variant), value.serialize(self)?]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList, SerdeError> {
+        Ok(SerializeList { items: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeList, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, SerdeError> {
+        let mut items = Vec::with_capacity(len + 1);
+        items.push(symbol(variant));
+        Ok(SerializeList { items })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, SerdeError> {
+        Ok(SerializeMap { entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeList, SerdeError> {
+        Ok(SerializeList { items: Vec::with_capacity(2 * len) })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, SerdeError> {
+        let mut items = Vec::with_capacity(2 * len + 1);
+        items.push(symbol(variant));
+        Ok(SerializeList { items })
+    }
+}
+
+/// Builds the list for a sequence, tuple, struct or enum variant.
+///
+/// Struct fields are pushed as a keyword followed by the value, and enum
+/// variants start with the variant name.
+#[derive(Debug)]
+pub struct SerializeList {
+    items: Vec<OwnedExpression>,
+}
+
+impl SerializeList {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.items.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn push_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError> {
+        self.items.push(OwnedExpression::Keyword(StringOwnedSymbol::from_str(key)));
+        self.push(value)
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+impl ser::SerializeStruct for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError> {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeList {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError> {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::List(self.items))
+    }
+}
+
+/// Builds a map literal from its entries.
+#[derive(Debug)]
+pub struct SerializeMap {
+    entries: Vec<(OwnedExpression, OwnedExpression)>,
+    key: Option<OwnedExpression>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = OwnedExpression;
+    type Error = SerdeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.key = Some(key.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let key = self.key.take().expect("serialize_value called before serialize_key");
+        self.entries.push((key, value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<OwnedExpression, SerdeError> {
+        Ok(OwnedExpression::Map(self.entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect(u32, u32),
+        Labeled { name: &'static str, sides: Option<u8> },
+    }
+
+    #[derive(Serialize)]
+    struct Unit;
+
+    #[derive(Serialize)]
+    struct Meters(f32);
+
+    #[test]
+    fn serialize_test() {
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Rect(2, 3),
+            Shape::Labeled { name: "tri", sides: Some(3) },
+        ];
+        assert_eq!(
+            to_string(&shapes).unwrap(),
+            "(Empty (Circle 1.5) (Rect 2 3) (Labeled :name \"tri\" :sides 3))",
+        );
+        assert_eq!(to_string(&(Unit, (), Meters(2.5), 'x')).unwrap(), "(null null 2.5 #\\x)");
+        assert_eq!(to_string(&BTreeMap::from([(1, vec!["a\"b"])])).unwrap(), "{1 (\"a\\\"b\")}");
+        assert_eq!(to_string(&i64::MIN).unwrap(), "-9223372036854775808");
+    }
+
+    #[test]
+    fn wide_integer_test() {
+        #[cfg(not(feature = "bignum"))]
+        assert!(matches!(to_expression(&u64::MAX), Err(SerdeError::Message(_))));
+        #[cfg(feature = "bignum")]
+        assert_eq!(to_string(&u64::MAX).unwrap(), "18446744073709551615");
+        assert_eq!(to_expression(&7u128).unwrap(), OwnedExpression::Integer(7));
+    }
+}
@@ -0,0 +1,185 @@
+//! Source Span Module
+//!
+//! This module provides source location tracking for parsed S-expressions.
+//! Rather than storing a location inside every [`Expression`] node, spans are
+//! kept in a parallel [`SpanTree`] that mirrors the list structure of the
+//! expression it describes. Plain [`read`](crate::read) stays allocation-light,
+//! and callers that need diagnostics opt in through
+//! [`read_spanned`](crate::read_spanned). When comments are kept (see
+//! [`ReaderOptions::keep_comments`](crate::ReaderOptions::keep_comments)),
+//! they are attached to the span tree as [`Comment`] trivia.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sexpression::read_spanned;
+//!
+//! let src = "(define x\n  (+ 1 2))";
+//! let spanned = read_spanned(src).unwrap();
+//!
+//! // The span of the third element, `(+ 1 2)`
+//! let inner = spanned.spans.get(&[2]).unwrap().span;
+//! assert_eq!(inner.source_text(src), "(+ 1 2)");
+//! assert_eq!((inner.line, inner.column), (2, 3));
+//! ```
+
+use crate::reader::{Expression, OwnedExpression, OwnedSymbol};
+
+/// A region of source text.
+///
+/// `start` and `end` are byte offsets into the source string (`end` is
+/// exclusive). `line` and `column` are the 1-based location of `start`, with
+/// columns counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset one past the last character
+    pub end: usize,
+    /// 1-based line number of `start`
+    pub line: usize,
+    /// 1-based column number of `start`
+    pub column: usize,
+}
+
+impl Span {
+    /// Length of the span in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the span covers no source text.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Create a span covering both `self` and `other`.
+    ///
+    /// The line and column are taken from whichever span starts first.
+    pub fn join(self, other: Span) -> Span {
+        let first = if self.start <= other.start { self } else { other };
+        Span {
+            start: first.start,
+            end: self.end.max(other.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+
+    /// Slice the source text covered by this span.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source string the span was produced from
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds for `src`
+    pub fn source_text<'a>(&self, src: &'a str) -> &'a str {
+        &src[self.start..self.end]
+    }
+}
+
+/// The syntax a comment was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentKind {
+    /// A `;` comment running to the end of the line
+    Line,
+    /// A `#| ... |#` block comment
+    Block,
+    /// A `#;` datum comment together with the expression it comments out
+    Datum,
+}
+
+/// A comment kept as trivia while reading.
+///
+/// Only the location is stored; use [`Span::source_text`] to get the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Comment {
+    /// The comment syntax
+    pub kind: CommentKind,
+    /// The span of the whole comment, including its delimiters
+    pub span: Span,
+}
+
+/// Source spans for an expression and, recursively, its children.
+///
+/// The tree has exactly the shape of the expression it was produced with:
+/// atoms have no children and a list's `children` line up one-to-one with
+/// its elements. Vectors and sets work the same way, and a map's children
+/// are its keys and values in source order.
+///
+/// When comments are kept, each comment is attached to the node that
+/// follows it as `leading` trivia. Comments between the last element of a
+/// list and its closing parenthesis have no following node and are attached
+/// to the list as `trailing` trivia instead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpanTree {
+    /// The span of the whole expression
+    pub span: Span,
+    /// Spans of the list elements, empty for atoms
+    pub children: Vec<SpanTree>,
+    /// Comments directly preceding this expression
+    pub leading: Vec<Comment>,
+    /// Comments before the closing parenthesis of a list
+    pub trailing: Vec<Comment>,
+}
+
+impl SpanTree {
+    /// Create a span tree with no children.
+    pub fn leaf(span: Span) -> Self {
+        SpanTree { span, ..SpanTree::default() }
+    }
+
+    /// Look up the spans of a nested sub-expression.
+    ///
+    /// Each entry of `path` is an index into the children of the previous
+    /// level. An empty path returns `self`.
+    ///
+    /// # Returns
+    ///
+    /// The span tree at `path`, or `None` if the path does not exist
+    pub fn get(&self, path: &[usize]) -> Option<&SpanTree> {
+        path.iter().try_fold(self, |tree, &i| tree.children.get(i))
+    }
+}
+
+/// An expression paired with its source spans.
+///
+/// This is produced by [`read_spanned`](crate::read_spanned) for borrowed
+/// expressions and can be converted into an owned expression with
+/// [`Spanned::to_owned`] without losing location information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<E> {
+    /// The parsed expression
+    pub expr: E,
+    /// Source spans mirroring the structure of `expr`
+    pub spans: SpanTree,
+}
+
+impl<E> Spanned<E> {
+    /// The span of the whole expression.
+    pub fn span(&self) -> Span {
+        self.spans.span
+    }
+}
+
+impl<'a> Spanned<Expression<'a>> {
+    /// Convert to an owned expression, keeping the span tree.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sexpression::{read_spanned, OwnedExpression, Spanned, StringOwnedSymbol};
+    ///
+    /// let spanned = read_spanned("(a b)").unwrap();
+    /// let owned: Spanned<OwnedExpression<StringOwnedSymbol>> = spanned.to_owned();
+    /// assert_eq!(owned.span(), spanned.span());
+    /// ```
+    pub fn to_owned<S: OwnedSymbol>(&self) -> Spanned<OwnedExpression<S>> {
+        Spanned {
+            expr: self.expr.to_owned(),
+            spans: self.spans.clone(),
+        }
+    }
+}